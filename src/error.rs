@@ -1,6 +1,21 @@
+use std::error::Error;
+use std::fmt;
+use indexmap::IndexMap;
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
 use serde::ser::{Serialize, Serializer, SerializeSeq};
+use crate::messages;
+use crate::store::StoreError;
 
-#[derive(Debug)]
+/// Error of a single validator
+///
+/// Serializes to its code (e.g. `"required_error"`), or to `[code, params]`
+/// for errors with parameters (e.g. `["min_len_error", [8, 3]]`), and
+/// deserializes back from the same format. Store failures are serialized
+/// without their cause, so they deserialize to a generic `StoreError`.
+///
+/// `Display` renders the built-in English message, with "value" as the field
+/// name; use `messages::Messages` for field names and other locales.
+#[derive(Debug, Clone, PartialEq)]
 pub enum ValidationError {
     Required,
     TypeError { expected: String, got: String },
@@ -21,11 +36,140 @@ pub enum ValidationError {
     IpError(String),
     ExtensionError(Vec<String>),
     UniqueError,
+    ExistsError,
     FileSizeError { min: u64, max: u64 },
+    SameError(String),
+    DifferentError(String),
+    ConfirmedError,
+    GtFieldError(String),
+    LtFieldError(String),
+    RequiredIfError { field: String, value: String },
+    RequiredUnlessError { field: String, value: String },
+    RequiredWithError(Vec<String>),
+    RequiredWithAllError(Vec<String>),
+    RequiredWithoutError(Vec<String>),
+    ProhibitedIfError { field: String, value: String },
+    UnknownField,
+    Nested(ValidationErrors),
+    /// The data store failed; not a problem with the input
+    Store(StoreError),
+    /// Async validation ran out of time; not a problem with the input
+    Timeout,
+    /// Error replaced by a custom message (or message catalog key)
+    Message { message: String, error: Box<ValidationError> },
 
     Custom(String),
 }
 
+impl ValidationError {
+    /// Machine code of the error, as used in its serialized form
+    pub fn code(&self) -> &'static str {
+        match self {
+            ValidationError::Required => "required_error",
+            ValidationError::TypeError { .. } => "type_error",
+            ValidationError::LengthError { .. } => "len_error",
+            ValidationError::MinLengthError { .. } => "min_len_error",
+            ValidationError::MaxLengthError { .. } => "max_len_error",
+            ValidationError::EqualError { .. } => "eq_error",
+            ValidationError::MinValueError { .. } => "min_error",
+            ValidationError::MaxValueError { .. } => "max_error",
+            ValidationError::NumericError(_) => "numeric_error",
+            ValidationError::AcceptedError(_) => "accepted_error",
+            ValidationError::EmailError(_) => "email_error",
+            ValidationError::EmailDomainError(_) => "email_domain_name_error",
+            ValidationError::InError(_) => "in_error",
+            ValidationError::NotInError(_) => "not_in_error",
+            ValidationError::RegexError(_) => "regex_error",
+            ValidationError::UrlError(_) => "url_error",
+            ValidationError::IpError(_) => "ip_error",
+            ValidationError::ExtensionError(_) => "extension_error",
+            ValidationError::UniqueError => "unique_error",
+            ValidationError::ExistsError => "exists_error",
+            ValidationError::FileSizeError { .. } => "file_size_error",
+            ValidationError::SameError(_) => "same_error",
+            ValidationError::DifferentError(_) => "different_error",
+            ValidationError::ConfirmedError => "confirmed_error",
+            ValidationError::GtFieldError(_) => "gt_field_error",
+            ValidationError::LtFieldError(_) => "lt_field_error",
+            ValidationError::RequiredIfError { .. } => "required_if_error",
+            ValidationError::RequiredUnlessError { .. } => "required_unless_error",
+            ValidationError::RequiredWithError(_) => "required_with_error",
+            ValidationError::RequiredWithAllError(_) => "required_with_all_error",
+            ValidationError::RequiredWithoutError(_) => "required_without_error",
+            ValidationError::ProhibitedIfError { .. } => "prohibited_if_error",
+            ValidationError::UnknownField => "unknown_field_error",
+            ValidationError::Nested(_) => "nested_error",
+            ValidationError::Store(_) => "store_error",
+            ValidationError::Timeout => "timeout_error",
+            ValidationError::Message { .. } => "message_error",
+            ValidationError::Custom(_) => "validate_error",
+        }
+    }
+
+    /// Replaces the message of the error, keeping it as the cause
+    ///
+    /// Store failures and timeouts are returned unchanged, since they are not
+    /// about the input.
+    pub fn with_message(self, message: &str) -> ValidationError {
+        match self {
+            ValidationError::Store(_) | ValidationError::Timeout => self,
+            ValidationError::Message { error, .. } => ValidationError::Message {
+                message: message.to_string(),
+                error,
+            },
+            error => ValidationError::Message {
+                message: message.to_string(),
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// Named parameters of the error, the placeholders of its message
+    ///
+    /// e.g. `MinLengthError` has `min` and `got`, rendered into
+    /// ":field must be at least :min characters".
+    pub fn params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            ValidationError::TypeError { expected, got } => vec![("expected", expected.clone()), ("got", got.clone())],
+            ValidationError::LengthError { expected, got } => vec![("length", expected.to_string()), ("got", got.to_string())],
+            ValidationError::MinLengthError { expected, got } => vec![("min", expected.to_string()), ("got", got.to_string())],
+            ValidationError::MaxLengthError { expected, got } => vec![("max", expected.to_string()), ("got", got.to_string())],
+            ValidationError::EqualError { expected, got } => vec![("value", expected.clone()), ("got", got.clone())],
+            ValidationError::MinValueError { expected, got } => vec![("min", expected.to_string()), ("got", got.to_string())],
+            ValidationError::MaxValueError { expected, got } => vec![("max", expected.to_string()), ("got", got.to_string())],
+            ValidationError::NumericError(a)
+            | ValidationError::AcceptedError(a)
+            | ValidationError::EmailError(a)
+            | ValidationError::RegexError(a)
+            | ValidationError::UrlError(a)
+            | ValidationError::IpError(a) => vec![("value", a.clone())],
+            ValidationError::EmailDomainError(a) => vec![("domain", a.clone())],
+            ValidationError::InError(a) | ValidationError::NotInError(a) => vec![("values", a.clone())],
+            ValidationError::ExtensionError(a) => vec![("extensions", a.join(", "))],
+            ValidationError::FileSizeError { min, max } => vec![("min", min.to_string()), ("max", max.to_string())],
+            ValidationError::SameError(a)
+            | ValidationError::DifferentError(a)
+            | ValidationError::GtFieldError(a)
+            | ValidationError::LtFieldError(a) => vec![("other", a.clone())],
+            ValidationError::RequiredIfError { field, value }
+            | ValidationError::RequiredUnlessError { field, value }
+            | ValidationError::ProhibitedIfError { field, value } => vec![("other", field.clone()), ("value", value.clone())],
+            ValidationError::RequiredWithError(a)
+            | ValidationError::RequiredWithAllError(a)
+            | ValidationError::RequiredWithoutError(a) => vec![("others", a.join(", "))],
+            ValidationError::Store(e) => vec![("error", e.to_string())],
+            ValidationError::Custom(a) => vec![("message", a.clone())],
+            ValidationError::Message { error, .. } => error.params(),
+            ValidationError::Required
+            | ValidationError::UniqueError
+            | ValidationError::ExistsError
+            | ValidationError::ConfirmedError
+            | ValidationError::UnknownField
+            | ValidationError::Nested(_)
+            | ValidationError::Timeout => Vec::new(),
+        }
+    }
+}
 
 impl Serialize for ValidationError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -141,12 +285,99 @@ impl Serialize for ValidationError {
             ValidationError::UniqueError => {
                 Ok(serializer.serialize_str("unique_error")?)
             }
+            ValidationError::ExistsError => {
+                Ok(serializer.serialize_str("exists_error")?)
+            }
             ValidationError::FileSizeError { min, max } => {
                 let mut seq = serializer.serialize_seq(Some(2))?;
                 seq.serialize_element("file_size_error")?;
                 seq.serialize_element(&[min,max])?;
                 seq.end()
             }
+            ValidationError::SameError(a) => {
+                let mut seq = serializer.serialize_seq(Some(2))?;
+                seq.serialize_element("same_error")?;
+                seq.serialize_element(&[a])?;
+                seq.end()
+            }
+            ValidationError::DifferentError(a) => {
+                let mut seq = serializer.serialize_seq(Some(2))?;
+                seq.serialize_element("different_error")?;
+                seq.serialize_element(&[a])?;
+                seq.end()
+            }
+            ValidationError::ConfirmedError => {
+                Ok(serializer.serialize_str("confirmed_error")?)
+            }
+            ValidationError::GtFieldError(a) => {
+                let mut seq = serializer.serialize_seq(Some(2))?;
+                seq.serialize_element("gt_field_error")?;
+                seq.serialize_element(&[a])?;
+                seq.end()
+            }
+            ValidationError::LtFieldError(a) => {
+                let mut seq = serializer.serialize_seq(Some(2))?;
+                seq.serialize_element("lt_field_error")?;
+                seq.serialize_element(&[a])?;
+                seq.end()
+            }
+            ValidationError::RequiredIfError { field, value } => {
+                let mut seq = serializer.serialize_seq(Some(2))?;
+                seq.serialize_element("required_if_error")?;
+                seq.serialize_element(&[field,value])?;
+                seq.end()
+            }
+            ValidationError::RequiredUnlessError { field, value } => {
+                let mut seq = serializer.serialize_seq(Some(2))?;
+                seq.serialize_element("required_unless_error")?;
+                seq.serialize_element(&[field,value])?;
+                seq.end()
+            }
+            ValidationError::RequiredWithError(a) => {
+                let mut seq = serializer.serialize_seq(Some(2))?;
+                seq.serialize_element("required_with_error")?;
+                seq.serialize_element(&[a])?;
+                seq.end()
+            }
+            ValidationError::RequiredWithAllError(a) => {
+                let mut seq = serializer.serialize_seq(Some(2))?;
+                seq.serialize_element("required_with_all_error")?;
+                seq.serialize_element(&[a])?;
+                seq.end()
+            }
+            ValidationError::RequiredWithoutError(a) => {
+                let mut seq = serializer.serialize_seq(Some(2))?;
+                seq.serialize_element("required_without_error")?;
+                seq.serialize_element(&[a])?;
+                seq.end()
+            }
+            ValidationError::ProhibitedIfError { field, value } => {
+                let mut seq = serializer.serialize_seq(Some(2))?;
+                seq.serialize_element("prohibited_if_error")?;
+                seq.serialize_element(&[field,value])?;
+                seq.end()
+            }
+            ValidationError::UnknownField => {
+                Ok(serializer.serialize_str("unknown_field_error")?)
+            }
+            ValidationError::Nested(a) => {
+                let mut seq = serializer.serialize_seq(Some(2))?;
+                seq.serialize_element("nested_error")?;
+                seq.serialize_element(a)?;
+                seq.end()
+            }
+            ValidationError::Store(_) => {
+                Ok(serializer.serialize_str("store_error")?)
+            }
+            ValidationError::Timeout => {
+                Ok(serializer.serialize_str("timeout_error")?)
+            }
+            ValidationError::Message { message, error } => {
+                let mut seq = serializer.serialize_seq(Some(2))?;
+                seq.serialize_element("message_error")?;
+                seq.serialize_element(&(message, error))?;
+                seq.end()
+            }
             ValidationError::Custom(a) => {
                 let mut seq = serializer.serialize_seq(Some(2))?;
                 seq.serialize_element("validate_error")?;
@@ -155,4 +386,480 @@ impl Serialize for ValidationError {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+impl<'de> Deserialize<'de> for ValidationError {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ValidationErrorVisitor)
+    }
+}
+
+struct ValidationErrorVisitor;
+
+impl<'de> Visitor<'de> for ValidationErrorVisitor {
+    type Value = ValidationError;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an error code or a [code, params] sequence")
+    }
+
+    fn visit_str<E>(self, code: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match code {
+            "required_error" => Ok(ValidationError::Required),
+            "unique_error" => Ok(ValidationError::UniqueError),
+            "exists_error" => Ok(ValidationError::ExistsError),
+            "confirmed_error" => Ok(ValidationError::ConfirmedError),
+            "unknown_field_error" => Ok(ValidationError::UnknownField),
+            "store_error" => Ok(ValidationError::Store(StoreError::new("cause not serialized"))),
+            "timeout_error" => Ok(ValidationError::Timeout),
+            _ => Err(E::unknown_variant(code, UNIT_CODES)),
+        }
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let code: String = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let error = match code.as_str() {
+            "type_error" => {
+                let [expected, got] = params(&mut seq)?;
+                ValidationError::TypeError { expected, got }
+            }
+            "len_error" => {
+                let [expected, got] = params(&mut seq)?;
+                ValidationError::LengthError { expected, got }
+            }
+            "min_len_error" => {
+                let [expected, got] = params(&mut seq)?;
+                ValidationError::MinLengthError { expected, got }
+            }
+            "max_len_error" => {
+                let [expected, got] = params(&mut seq)?;
+                ValidationError::MaxLengthError { expected, got }
+            }
+            "eq_error" => {
+                let [expected, got] = params(&mut seq)?;
+                ValidationError::EqualError { expected, got }
+            }
+            "min_error" => {
+                let [expected, got] = params(&mut seq)?;
+                ValidationError::MinValueError { expected, got }
+            }
+            "max_error" => {
+                let [expected, got] = params(&mut seq)?;
+                ValidationError::MaxValueError { expected, got }
+            }
+            "numeric_error" => ValidationError::NumericError(param(&mut seq)?),
+            "accepted_error" => ValidationError::AcceptedError(param(&mut seq)?),
+            "email_error" => ValidationError::EmailError(param(&mut seq)?),
+            "email_domain_name_error" => ValidationError::EmailDomainError(param(&mut seq)?),
+            "in_error" => ValidationError::InError(param(&mut seq)?),
+            "not_in_error" => ValidationError::NotInError(param(&mut seq)?),
+            "regex_error" => ValidationError::RegexError(param(&mut seq)?),
+            "url_error" => ValidationError::UrlError(param(&mut seq)?),
+            "ip_error" => ValidationError::IpError(param(&mut seq)?),
+            "extension_error" => ValidationError::ExtensionError(param(&mut seq)?),
+            "file_size_error" => {
+                let [min, max] = params(&mut seq)?;
+                ValidationError::FileSizeError { min, max }
+            }
+            "same_error" => ValidationError::SameError(param(&mut seq)?),
+            "different_error" => ValidationError::DifferentError(param(&mut seq)?),
+            "gt_field_error" => ValidationError::GtFieldError(param(&mut seq)?),
+            "lt_field_error" => ValidationError::LtFieldError(param(&mut seq)?),
+            "required_if_error" => {
+                let [field, value] = params(&mut seq)?;
+                ValidationError::RequiredIfError { field, value }
+            }
+            "required_unless_error" => {
+                let [field, value] = params(&mut seq)?;
+                ValidationError::RequiredUnlessError { field, value }
+            }
+            "required_with_error" => ValidationError::RequiredWithError(param(&mut seq)?),
+            "required_with_all_error" => ValidationError::RequiredWithAllError(param(&mut seq)?),
+            "required_without_error" => ValidationError::RequiredWithoutError(param(&mut seq)?),
+            "prohibited_if_error" => {
+                let [field, value] = params(&mut seq)?;
+                ValidationError::ProhibitedIfError { field, value }
+            }
+            "nested_error" => ValidationError::Nested(element(&mut seq)?),
+            "message_error" => {
+                let (message, error): (String, ValidationError) = element(&mut seq)?;
+                ValidationError::Message { message, error: Box::new(error) }
+            }
+            "validate_error" => ValidationError::Custom(param(&mut seq)?),
+            code => return Err(de::Error::unknown_variant(code, SEQ_CODES)),
+        };
+        if seq.next_element::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(3, &self));
+        }
+        Ok(error)
+    }
+}
+
+const UNIT_CODES: &[&str] = &[
+    "required_error", "unique_error", "exists_error", "confirmed_error",
+    "unknown_field_error", "store_error", "timeout_error",
+];
+
+const SEQ_CODES: &[&str] = &[
+    "type_error", "len_error", "min_len_error", "max_len_error", "eq_error", "min_error",
+    "max_error", "numeric_error", "accepted_error", "email_error", "email_domain_name_error",
+    "in_error", "not_in_error", "regex_error", "url_error", "ip_error", "extension_error",
+    "file_size_error", "same_error", "different_error", "gt_field_error", "lt_field_error",
+    "required_if_error", "required_unless_error", "required_with_error",
+    "required_with_all_error", "required_without_error", "prohibited_if_error",
+    "nested_error", "message_error", "validate_error",
+];
+
+/// Second element of a `[code, params]` sequence
+fn element<'de, A, T>(seq: &mut A) -> Result<T, A::Error>
+where
+    A: SeqAccess<'de>,
+    T: Deserialize<'de>,
+{
+    seq.next_element()?.ok_or_else(|| de::Error::invalid_length(1, &"a [code, params] sequence"))
+}
+
+/// Pair of parameters, e.g. `[expected, got]`
+fn params<'de, A, T>(seq: &mut A) -> Result<[T; 2], A::Error>
+where
+    A: SeqAccess<'de>,
+    T: Deserialize<'de>,
+{
+    element(seq)
+}
+
+/// Single parameter, e.g. `[field]`
+fn param<'de, A, T>(seq: &mut A) -> Result<T, A::Error>
+where
+    A: SeqAccess<'de>,
+    T: Deserialize<'de>,
+{
+    let [value]: [T; 1] = element(seq)?;
+    Ok(value)
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let template = match self {
+            ValidationError::Store(e) => return e.fmt(f),
+            ValidationError::Message { message, .. } => message.as_str(),
+            _ => messages::english_template(self.code()).unwrap_or(self.code()),
+        };
+        f.write_str(&messages::fill(template, "value", self))
+    }
+}
+
+impl Error for ValidationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ValidationError::Store(e) => Some(e),
+            ValidationError::Message { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Errors of a form, keyed by field path
+///
+/// Entries keep the order in which fields were validated. Errors about the
+/// form as a whole (e.g. failing to build the output document) are stored
+/// under the empty path, see `form_errors`.
+///
+/// Serializes to a map of path to error list, e.g.
+/// `{"email": ["required_error"], "": [["validate_error", ["..."]]]}`.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+/// use validate_ro::{rules, FormValidator};
+/// use validate_ro::error::ValidationError;
+/// use validate_ro::rules::Rule;
+///
+/// let address = FormValidator::new().add("zip", rules![Rule::required()]);
+/// let validator = FormValidator::new()
+///     .add("email", rules![Rule::required(), Rule::email(None)])
+///     .add("address", address);
+///
+/// let errors = validator.validate_json(&json!({"address": {}})).unwrap_err();
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(errors.get("address.zip"), Some(&[ValidationError::Required][..]));
+/// assert!(errors.form_errors().is_empty());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationErrors(IndexMap<String, Vec<ValidationError>>);
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an error to a field
+    pub fn add(&mut self, path: impl Into<String>, error: ValidationError) {
+        self.0.entry(path.into()).or_default().push(error);
+    }
+
+    /// Adds errors to a field, after those it already has
+    pub fn add_all(&mut self, path: impl Into<String>, errors: impl IntoIterator<Item = ValidationError>) {
+        self.0.entry(path.into()).or_default().extend(errors);
+    }
+
+    /// Adds an error about the form as a whole
+    pub fn add_form_error(&mut self, error: ValidationError) {
+        self.add("", error);
+    }
+
+    /// Errors of a field
+    ///
+    /// Paths reach into `ValidationError::Nested` errors, so "address.zip"
+    /// finds the errors of "zip" in a nested error of "address".
+    pub fn get(&self, path: &str) -> Option<&[ValidationError]> {
+        if let Some(errors) = self.0.get(path) {
+            return Some(errors);
+        }
+        self.0.iter().find_map(|(field, errors)| {
+            let rest = path.strip_prefix(field.as_str())?.strip_prefix('.')?;
+            errors.iter().find_map(|error| match error {
+                ValidationError::Nested(nested) => nested.get(rest),
+                _ => None,
+            })
+        })
+    }
+
+    /// Every error with its path, listing the errors of `ValidationError::Nested`
+    /// under prefixed paths instead of the nested error itself
+    pub(crate) fn flatten(&self) -> Vec<(String, &ValidationError)> {
+        let mut flat = Vec::new();
+        self.flatten_into("", &mut flat);
+        flat
+    }
+
+    fn flatten_into<'a>(&'a self, prefix: &str, flat: &mut Vec<(String, &'a ValidationError)>) {
+        for (field, errors) in &self.0 {
+            let path = prefixed(prefix, field);
+            for error in errors {
+                match error {
+                    ValidationError::Nested(nested) => nested.flatten_into(&path, flat),
+                    _ => flat.push((path.clone(), error)),
+                }
+            }
+        }
+    }
+
+    /// Errors about the form as a whole
+    pub fn form_errors(&self) -> &[ValidationError] {
+        self.0.get("").map_or(&[], Vec::as_slice)
+    }
+
+    pub fn contains_key(&self, path: &str) -> bool {
+        self.get(path).is_some()
+    }
+
+    /// Paths with errors, in order
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.0.keys()
+    }
+
+    /// Paths with their errors, in order
+    pub fn iter(&self) -> indexmap::map::Iter<'_, String, Vec<ValidationError>> {
+        self.0.iter()
+    }
+
+    /// Number of paths with errors, including the form itself if it has any
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Moves the errors of `other` into this report, after any existing
+    /// errors of the same paths
+    pub fn merge(&mut self, other: ValidationErrors) {
+        for (path, errors) in other {
+            self.add_all(path, errors);
+        }
+    }
+
+    /// Like `merge`, prefixing the paths of `other` with `prefix`
+    ///
+    /// Form-level errors of `other` become errors of `prefix` itself.
+    pub fn merge_prefixed(&mut self, prefix: &str, other: ValidationErrors) {
+        for (path, errors) in other {
+            self.add_all(prefixed(prefix, &path), errors);
+        }
+    }
+}
+
+/// Escapes a key for use as one segment of a dotted field path
+///
+/// Dots and backslashes are prefixed with a backslash: `a.b` becomes the
+/// segment `a\.b`.
+pub(crate) fn escape_path_segment(key: &str) -> String {
+    key.replace('\\', "\\\\").replace('.', "\\.")
+}
+
+/// Unescaped segments of a dotted field path, see `escape_path_segment`
+pub(crate) fn path_segments(path: &str) -> Vec<String> {
+    let mut segments = vec![String::new()];
+    let mut chars = path.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => segments.last_mut().unwrap().extend(chars.next()),
+            '.' => segments.push(String::new()),
+            _ => segments.last_mut().unwrap().push(c),
+        }
+    }
+    segments
+}
+
+/// Path of `field` inside the field at `prefix`; the empty path is the field itself
+fn prefixed(prefix: &str, field: &str) -> String {
+    match (prefix.is_empty(), field.is_empty()) {
+        (true, _) => field.to_string(),
+        (false, true) => prefix.to_string(),
+        (false, false) => format!("{}.{}", prefix, field),
+    }
+}
+
+impl std::ops::Index<&str> for ValidationErrors {
+    type Output = [ValidationError];
+
+    /// Errors of a field
+    ///
+    /// # Panics
+    ///
+    /// Panics if the field has no errors.
+    fn index(&self, path: &str) -> &[ValidationError] {
+        self.get(path).unwrap_or_else(|| panic!("no errors for field {:?}", path))
+    }
+}
+
+impl IntoIterator for ValidationErrors {
+    type Item = (String, Vec<ValidationError>);
+    type IntoIter = indexmap::map::IntoIter<String, Vec<ValidationError>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ValidationErrors {
+    type Item = (&'a String, &'a Vec<ValidationError>);
+    type IntoIter = indexmap::map::Iter<'a, String, Vec<ValidationError>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl FromIterator<(String, Vec<ValidationError>)> for ValidationErrors {
+    fn from_iter<I: IntoIterator<Item = (String, Vec<ValidationError>)>>(iter: I) -> Self {
+        let mut errors = ValidationErrors::new();
+        for (path, field_errors) in iter {
+            errors.add_all(path, field_errors);
+        }
+        errors
+    }
+}
+
+impl Serialize for ValidationErrors {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ValidationErrors {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        IndexMap::deserialize(deserializer).map(ValidationErrors)
+    }
+}
+
+/// One line per error, e.g. "email: value is required"
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for (path, errors) in &self.0 {
+            for error in errors {
+                if !first {
+                    writeln!(f)?;
+                }
+                first = false;
+                match path.as_str() {
+                    "" => write!(f, "{}", error)?,
+                    path => write!(f, "{}: {}", path, error)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Error for ValidationErrors {}
+
+/// Failure of `FormValidator::validate_json_async`
+///
+/// Separates invalid input from data store failures and timeouts, so callers
+/// can tell a client error (e.g. 422) from an outage (e.g. 503).
+#[derive(Debug)]
+pub enum AsyncValidationError {
+    /// Some fields are invalid
+    Validation(ValidationErrors),
+    /// The data store failed while validating
+    Store(StoreError),
+    /// Validation did not finish within the configured timeout
+    Timeout,
+}
+
+impl AsyncValidationError {
+    /// Field errors, if the input was invalid
+    pub fn validation_errors(&self) -> Option<&ValidationErrors> {
+        match self {
+            AsyncValidationError::Validation(errors) => Some(errors),
+            AsyncValidationError::Store(_) | AsyncValidationError::Timeout => None,
+        }
+    }
+}
+
+impl fmt::Display for AsyncValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AsyncValidationError::Validation(errors) => {
+                write!(f, "validation failed for {} field(s)", errors.len())
+            }
+            AsyncValidationError::Store(e) => e.fmt(f),
+            AsyncValidationError::Timeout => write!(f, "validation timed out"),
+        }
+    }
+}
+
+impl Error for AsyncValidationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AsyncValidationError::Validation(_) | AsyncValidationError::Timeout => None,
+            AsyncValidationError::Store(e) => Some(e),
+        }
+    }
+}
+
+impl From<StoreError> for AsyncValidationError {
+    fn from(error: StoreError) -> Self {
+        AsyncValidationError::Store(error)
+    }
+}
@@ -4,13 +4,22 @@
 //! - Synchronous and asynchronous validation
 //! - Complex nested field validation
 //! - Custom validation rules
-//! - MongoDB integration for unique checks
-//! - Default values and error accumulation
+//! - Pluggable data stores for async checks, with MongoDB integration
+//! - Default values, value transformers and error accumulation
+//!
+//! ## Cargo Features
+//!
+//! - `mongodb` (default) - `Rule::unique`, the `Store` implementation for
+//!   `mongodb::Database` and the `FormValidator` methods that work with bson
+//!   `Document`s (`validate`, `validate_async`, `validate_many` and the
+//!   `validate_bson` ones). `validate_json` and its async and batch variants
+//!   return a serde_json `Map` with or without it.
+//! - `toml` - Loading message catalogs from TOML files.
 //!
 //! ## Core Concepts
 //!
 //! 1. **Validators**: Implement the `Validator` trait to create validation rules
-//! 2. **Rules**: Combine multiple validators with optional default values
+//! 2. **Rules**: Combine multiple validators and transformers with optional default values
 //! 3. **FormValidator**: Validate complete forms/objects with field-level rules
 //!
 //! ## Example: Basic Usage
@@ -37,7 +46,7 @@
 //!
 //! // Validate data
 //! let data = json!({"email": "test@example.com"});
-//! match validator.validate(&data) {
+//! match validator.validate_json(&data) {
 //!     Ok(valid_data) => {
 //!         // age will be 21 (default value)
 //!         println!("Valid data: {:?}", valid_data);
@@ -48,52 +57,128 @@
 //! }
 //! ```
 
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
 use std::any::Any;
-use std::collections::HashMap;
+use std::collections::HashSet;
+use std::pin::pin;
+use std::time::Duration;
 use async_trait::async_trait;
-use mongodb::bson::{to_bson, Document};
-use mongodb::{bson, Database};
-use serde_json::Value;
-use crate::error::ValidationError;
-use crate::traits::{ValidationResult, Validator};
+use futures_timer::Delay;
+use futures_util::future::{select, Either};
+use futures_util::stream::{self, StreamExt};
+use indexmap::IndexMap;
+#[cfg(feature = "mongodb")]
+use mongodb::bson::{Bson, Document};
+use serde_json::{Map, Value};
+use crate::error::{escape_path_segment, AsyncValidationError, ValidationError, ValidationErrors};
+use crate::rules::TypeValidator;
+use crate::store::{PrefetchedStore, RecordingStore, Store};
+#[cfg(feature = "mongodb")]
+use crate::rules::UniqueValidator;
+use crate::traits::{Context, Transformer, ValidationResult, Validator};
 
+pub mod messages;
+pub mod problem;
 pub mod rules;
+pub mod store;
+pub mod transforms;
 pub mod traits;
 pub mod error;
 mod macros;
-pub use macros::*;
+
+/// Fields validated at the same time by `FormValidator::validate_json_async`
+const DEFAULT_CONCURRENCY: usize = 10;
 
 /// Container for multiple validators with optional default value
 ///
+/// Validators and transformers run in the order they were added, so each
+/// validator sees the value produced by the transformers before it.
+///
 /// # Examples
 ///
 /// ```
 /// use validate_ro::{Rules};
 /// use serde_json::json;
 /// use validate_ro::rules::Rule;
+/// use validate_ro::transforms::Transform;
 ///
 /// let rule = Rules::new()
+///     .transform(Transform::trim())
 ///     .add(Rule::required())
 ///     .add(Rule::min_length(8))
 ///     .default(json!("default"));
+/// assert_eq!(rule.process(&json!("  password  ")).unwrap(), json!("password"));
 /// ```
 pub struct Rules {
-    validators: Vec<Box<dyn Validator+ Send + Sync>>,
+    steps: Vec<Step>,
     default_value: Option<Value>,
+    coerce: bool,
+    bail: bool,
+    timeout: Option<Duration>,
+}
+
+enum Step {
+    /// Validator and the message replacing its errors
+    Validate(Box<dyn Validator+ Send + Sync>, Option<String>),
+    Transform(Box<dyn Transformer>),
 }
 
 impl Rules {
     /// Creates a new empty Rules container
     pub fn new() -> Self {
         Self {
-            validators: Vec::new(),
+            steps: Vec::new(),
             default_value: None,
+            coerce: false,
+            bail: true,
+            timeout: None,
         }
     }
 
     /// Adds a validator to the rules chain
+    #[allow(clippy::should_implement_trait)]
     pub fn add<V: Validator + 'static>(mut self, validator: V) -> Self {
-        self.validators.push(Box::new(validator));
+        self.steps.push(Step::Validate(Box::new(validator), None));
+        self
+    }
+
+    /// Replaces the errors of the last added validator with a custom message
+    ///
+    /// The message can also be a key of a `Messages` catalog, and may use
+    /// the placeholders of the replaced error (e.g. ":min"). Store failures
+    /// and timeouts are never replaced. Does nothing if no validator was added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::Rules;
+    /// use validate_ro::error::ValidationError;
+    /// use validate_ro::rules::Rule;
+    ///
+    /// let password = Rules::new()
+    ///     .add(Rule::required())
+    ///     .add(Rule::min_length(8)).message("password too short");
+    /// let errors = password.process(&json!("abc")).unwrap_err();
+    /// assert!(matches!(&errors[0], ValidationError::Message { message, .. } if message == "password too short"));
+    /// ```
+    pub fn message(mut self, message: &str) -> Self {
+        let last = self.steps.iter_mut().rev().find_map(|step| match step {
+            Step::Validate(_, message) => Some(message),
+            Step::Transform(_) => None,
+        });
+        if let Some(last) = last {
+            *last = Some(message.to_string());
+        }
+        self
+    }
+
+    /// Adds a transformer that rewrites the value for the following steps
+    ///
+    /// The transformed value is what `FormValidator` returns in its output.
+    pub fn transform<T: Transformer + 'static>(mut self, transformer: T) -> Self {
+        self.steps.push(Step::Transform(Box::new(transformer)));
         self
     }
 
@@ -102,35 +187,291 @@ impl Rules {
         self.default_value = Some(default);
         self
     }
+
+    /// Converts string input to the type declared by a type rule
+    ///
+    /// Meant for form-encoded and query-string data where every value arrives
+    /// as a string: with `Rule::integer()` "42" becomes 42, with `Rule::float()`
+    /// "4.2" becomes 4.2 and with `Rule::boolean()` "true"/"on"/"1" become true.
+    /// Empty strings become null for these types. Coercion happens before any
+    /// other step, and the coerced value is returned in the output.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::Rules;
+    /// use validate_ro::rules::Rule;
+    ///
+    /// let age = Rules::new().coerce().add(Rule::integer()).add(Rule::min_value(18.0));
+    /// assert_eq!(age.process(&json!("42")).unwrap(), json!(42));
+    /// ```
+    pub fn coerce(mut self) -> Self {
+        self.coerce = true;
+        self
+    }
+
+    /// Stops at the first failing validator (default)
+    pub fn bail(mut self) -> Self {
+        self.bail = true;
+        self
+    }
+
+    /// Runs every validator and reports all of their errors
+    ///
+    /// A failing validator doesn't stop the chain, so a password field can
+    /// report its length, pattern and blacklist errors together. Added to
+    /// another `Rules`, the chain reports all of its errors there too; used
+    /// through the `Validator` trait, several errors are returned as one
+    /// `ValidationError::Nested`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::Rules;
+    /// use validate_ro::rules::Rule;
+    ///
+    /// let password = Rules::new()
+    ///     .collect_all()
+    ///     .add(Rule::min_length(8))
+    ///     .add(Rule::regex(r"[0-9]", None).unwrap());
+    /// assert_eq!(password.process(&json!("abc")).unwrap_err().len(), 2);
+    /// ```
+    pub fn collect_all(mut self) -> Self {
+        self.bail = false;
+        self
+    }
+
+    /// Limits how long each validator may take in async validation
+    ///
+    /// A validator that runs out of time fails with `ValidationError::Timeout`
+    /// and stops the chain; `FormValidator::validate_json_async` reports it as
+    /// `AsyncValidationError::Timeout`.
+    pub fn timeout(mut self, limit: Duration) -> Self {
+        self.timeout = Some(limit);
+        self
+    }
+
+    /// Number of validators in the chain
     pub fn len(&self) -> usize {
-        self.validators.len()
+        self.validators().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Validates a value and returns it after defaults and transformers
+    ///
+    /// On failure returns the first error, or every error with `collect_all`.
+    pub fn process(&self, value: &Value) -> Result<Value, Vec<ValidationError>> {
+        self.process_with_context(value, None, false)
+    }
+
+    /// Validates a bson value and returns it after defaults and transformers
+    ///
+    /// See `FormValidator::validate_bson` for how bson types are handled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use mongodb::bson::{oid::ObjectId, Bson};
+    /// use validate_ro::Rules;
+    /// use validate_ro::rules::Rule;
+    ///
+    /// let id = Bson::ObjectId(ObjectId::new());
+    /// let rule = Rules::new().add(Rule::required()).add(Rule::object_id());
+    /// assert_eq!(rule.process_bson(&id).unwrap(), id);
+    /// ```
+    #[cfg(feature = "mongodb")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mongodb")))]
+    pub fn process_bson(&self, value: &Bson) -> Result<Bson, Vec<ValidationError>> {
+        let processed = self.process(&value.clone().into_relaxed_extjson())?;
+        for (rule, message) in type_rules(self) {
+            if let Err(error) = rule.check_width(&processed, value) {
+                return Err(vec![with_field_message(error, message)]);
+            }
+        }
+        let mut output = Bson::try_from(processed)
+            .map_err(|e| vec![ValidationError::Custom(e.to_string())])?;
+        restore_int64(&mut output, value);
+        Ok(output)
+    }
+
+    fn validators(&self) -> impl Iterator<Item = &(dyn Validator + Send + Sync)> {
+        self.steps.iter().filter_map(|step| match step {
+            Step::Validate(validator, _) => Some(validator.as_ref()),
+            Step::Transform(_) => None,
+        })
+    }
+
+    fn initial_value(&self, value: &Value, coerce: bool) -> Value {
+        let value = match &self.default_value {
+            Some(default) if value.is_null() => default.clone(),
+            _ => value.clone(),
+        };
+        if !(coerce || self.coerce) {
+            return value;
+        }
+        match self.validators().find_map(|validator| validator.as_any().downcast_ref::<TypeValidator>()) {
+            Some(type_rule) => type_rule.coerce(value),
+            None => value,
+        }
+    }
+
+    fn process_with_context(&self, value: &Value, ctx: Option<&Context<'_>>, coerce: bool) -> Result<Value, Vec<ValidationError>> {
+        let mut value = self.initial_value(value, coerce);
+        let mut errors = Vec::new();
+        for step in &self.steps {
+            let result = match step {
+                Step::Validate(validator, _) => validate_step(validator.as_ref(), &mut value, ctx, coerce),
+                Step::Transform(transformer) => {
+                    value = transformer.transform(value);
+                    Ok(())
+                }
+            };
+            if let Err(step_errors) = result {
+                errors.extend(step_errors.into_iter().map(|err| step.apply_message(err)));
+                if self.bail {
+                    break;
+                }
+            }
+        }
+        if errors.is_empty() { Ok(value) } else { Err(errors) }
+    }
+
+    async fn process_async_with_context(&self, store: &dyn Store, value: &Value, ctx: Option<&Context<'_>>, coerce: bool) -> Result<Value, Vec<ValidationError>> {
+        let mut value = self.initial_value(value, coerce);
+        let mut errors = Vec::new();
+        for step in &self.steps {
+            let result = match step {
+                Step::Validate(validator, _) => {
+                    with_timeout(self.timeout, validate_step_async(validator.as_ref(), store, &mut value, ctx, coerce)).await
+                }
+                Step::Transform(transformer) => {
+                    value = transformer.transform(value);
+                    Some(Ok(()))
+                }
+            };
+            match result {
+                Some(Ok(())) => {}
+                Some(Err(step_errors)) => {
+                    let timed_out = step_errors.contains(&ValidationError::Timeout);
+                    errors.extend(step_errors.into_iter().map(|err| step.apply_message(err)));
+                    if self.bail || timed_out {
+                        break;
+                    }
+                }
+                None => {
+                    errors.push(ValidationError::Timeout);
+                    break;
+                }
+            }
+        }
+        if errors.is_empty() { Ok(value) } else { Err(errors) }
+    }
+}
+
+/// Runs one validator of a `Rules` chain
+///
+/// A nested `Rules` is run as part of the chain, so that all of its errors
+/// are kept rather than the single error of its `Validator` methods, and a
+/// nested `FormValidator` validates the value as a schema. Both replace the
+/// value with the one they produce, so that e.g. keys the schema doesn't
+/// know are stripped.
+fn validate_step(validator: &(dyn Validator + Send + Sync), value: &mut Value, ctx: Option<&Context<'_>>, coerce: bool) -> Result<(), Vec<ValidationError>> {
+    if let Some(rules) = validator.as_any().downcast_ref::<Rules>() {
+        *value = rules.process_with_context(value, ctx, coerce)?;
+        return Ok(());
+    }
+    if let Some(schema) = validator.as_any().downcast_ref::<FormValidator>() {
+        check_nested_type(value).map_err(|err| vec![err])?;
+        let (nested_data, nested_errors) = schema.collect(value, coerce || schema.coerce);
+        *value = nested_output(value, nested_data, nested_errors)?;
+        return Ok(());
+    }
+    let result = match ctx {
+        Some(ctx) => validator.validate_with_context(value, ctx),
+        None => validator.validate(value),
+    };
+    result.map_err(|err| vec![err])
+}
+
+async fn validate_step_async(validator: &(dyn Validator + Send + Sync), store: &dyn Store, value: &mut Value, ctx: Option<&Context<'_>>, coerce: bool) -> Result<(), Vec<ValidationError>> {
+    if let Some(rules) = validator.as_any().downcast_ref::<Rules>() {
+        *value = Box::pin(rules.process_async_with_context(store, value, ctx, coerce)).await?;
+        return Ok(());
+    }
+    if let Some(schema) = validator.as_any().downcast_ref::<FormValidator>() {
+        check_nested_type(value).map_err(|err| vec![err])?;
+        let (nested_data, nested_errors) = Box::pin(schema.collect_async(store, value, coerce || schema.coerce)).await;
+        *value = nested_output(value, nested_data, nested_errors)?;
+        return Ok(());
+    }
+    let result = match ctx {
+        Some(ctx) => validator.validate_async_with_context(store, value, ctx).await,
+        None => validator.validate_async(store, value).await,
+    };
+    result.map_err(|err| vec![err])
+}
+
+/// Validated object of a schema run as a `Rules` step, or its errors as one
+/// `ValidationError::Nested`
+fn nested_output(value: &Value, nested_data: IndexMap<String, Value>, nested_errors: ValidationErrors) -> Result<Value, Vec<ValidationError>> {
+    if nested_errors.is_empty() {
+        Ok(Value::Object(nest_fields(value, nested_data, false)))
+    } else {
+        Err(vec![ValidationError::Nested(nested_errors)])
+    }
+}
+
+impl Step {
+    fn apply_message(&self, error: ValidationError) -> ValidationError {
+        match self {
+            Step::Validate(_, Some(message)) => error.with_message(message),
+            _ => error,
+        }
+    }
+}
+
+/// `Validator` methods report a single error: a store failure or timeout
+/// if the chain had one, its only error, or else every error of the chain
+/// as a `ValidationError::Nested` holding them at the empty path
+fn single_error(mut errors: Vec<ValidationError>) -> ValidationError {
+    let infrastructure = errors.iter()
+        .position(|error| matches!(error, ValidationError::Store(_) | ValidationError::Timeout));
+    if let Some(index) = infrastructure {
+        return errors.swap_remove(index);
+    }
+    if errors.len() == 1 {
+        return errors.remove(0);
+    }
+    ValidationError::Nested(ValidationErrors::from_iter([(String::new(), errors)]))
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[async_trait]
 impl Validator for Rules {
     fn validate(&self, value: &Value) -> ValidationResult {
-        let value = if value.is_null() && self.default_value.is_some() {
-            self.default_value.as_ref().unwrap()
-        } else {
-            value
-        };
-        for validator in &self.validators {
-            validator.validate(value)?;
-        }
-        Ok(())
+        self.process_with_context(value, None, false).map(|_| ()).map_err(single_error)
     }
 
-    async fn validate_async(&self, _db: &Database, value: &Value) -> ValidationResult {
-        let value = if value.is_null() && self.default_value.is_some() {
-            self.default_value.as_ref().unwrap()
-        } else {
-            value
-        };
-        for validator in &self.validators {
-            validator.validate_async(_db,value).await?;
-        }
-        Ok(())
+    async fn validate_async(&self, store: &dyn Store, value: &Value) -> ValidationResult {
+        self.process_async_with_context(store, value, None, false).await.map(|_| ()).map_err(single_error)
+    }
+
+    fn validate_with_context(&self, value: &Value, ctx: &Context<'_>) -> ValidationResult {
+        self.process_with_context(value, Some(ctx), false).map(|_| ()).map_err(single_error)
+    }
+
+    async fn validate_async_with_context(&self, store: &dyn Store, value: &Value, ctx: &Context<'_>) -> ValidationResult {
+        self.process_async_with_context(store, value, Some(ctx), false).await.map(|_| ()).map_err(single_error)
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -139,12 +480,33 @@ impl Validator for Rules {
 }
 
 
+/// How `FormValidator` handles input keys that have no registered rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strictness {
+    /// Leaves unknown keys out of the output (default)
+    Strip,
+    /// Reports an `UnknownField` error for every unknown key, including keys
+    /// of nested objects and array elements; dots and backslashes of a key
+    /// are escaped with a backslash in its path (e.g. `profile.a\.b`)
+    Reject,
+    /// Copies unknown keys into the output unvalidated
+    ///
+    /// Keys containing a dot are left out, as with `Strip`, since they would
+    /// be read as nested paths.
+    Passthrough,
+}
+
 /// Validates complete forms/objects with field-level rules
 ///
 /// Supports:
 /// - Nested field paths (e.g., "user.address.street")
+/// - Array paths with wildcards (e.g., "items.*.sku") and indexes (e.g., "items.0.sku")
+/// - Cross-field rules that compare a field with other fields of the form
+/// - Stripping, rejecting or passing through unknown fields
+/// - Nested `FormValidator` schemas for object and array-of-object fields
+/// - Validation, errors and output in the order fields were added
 /// - Early termination on first error
-/// - Async validation with MongoDB
+/// - Async validation against a data store (e.g. MongoDB)
 ///
 /// # Example
 ///
@@ -154,11 +516,18 @@ impl Validator for Rules {
 ///
 /// let validator = FormValidator::new()
 ///     .add("username", Rule::required())
-///     .add("profile.age", Rule::integer());
+///     .add("profile.age", Rule::integer())
+///     .add("items.*.sku", Rule::required());
 /// ```
 pub struct FormValidator {
     break_on_error:bool,
-    field_validators: HashMap<String, Box<dyn Validator+ Send + Sync>>,
+    strictness:Strictness,
+    flat_output:bool,
+    coerce:bool,
+    concurrency:usize,
+    timeout:Option<Duration>,
+    field_messages: IndexMap<String, String>,
+    field_validators: IndexMap<String, Box<dyn Validator+ Send + Sync>>,
 }
 
 impl FormValidator {
@@ -166,7 +535,13 @@ impl FormValidator {
     pub fn new() -> Self {
         Self {
             break_on_error:false,
-            field_validators: HashMap::new(),
+            strictness:Strictness::Strip,
+            flat_output:false,
+            coerce:false,
+            concurrency:DEFAULT_CONCURRENCY,
+            timeout:None,
+            field_messages: IndexMap::new(),
+            field_validators: IndexMap::new(),
         }
     }
 
@@ -174,8 +549,10 @@ impl FormValidator {
     ///
     /// # Arguments
     ///
-    /// * `field_name` - Field path (supports dot notation for nested fields)
+    /// * `field_name` - Field path (supports dot notation for nested fields,
+    ///   `*` to match every element of an array and numeric segments to pick one element)
     /// * `validator` - Validation rules
+    #[allow(clippy::should_implement_trait)]
     pub fn add(
         mut self,
         field_name: &str,
@@ -188,126 +565,537 @@ impl FormValidator {
 
     /// Validates form data synchronously
     ///
+    /// Fields with wildcard paths are validated once per array element and
+    /// reported under the concrete path (e.g. "items.3.sku").
+    ///
     /// Returns either:
-    /// - Ok(Document) with validated values (including defaults)
-    /// - Err(HashMap) with field names and error lists
+    /// - Ok(Map) with validated values (including defaults), nested by path
+    ///   unless `flat_output` is set
+    /// - Err(ValidationErrors) with the errors of each field path
+    ///
+    /// Fields are validated in the order they were added, which is also the
+    /// order of the errors and of the output map.
+    pub fn validate_json(
+        &self,
+        form_data: &Value,
+    ) -> Result<Map<String, Value>, ValidationErrors> {
+        let (valid_data, errors) = self.collect(form_data, self.coerce);
+        self.output_with(form_data, valid_data, errors, Ok)
+    }
+
+    /// Validates form data synchronously into a bson `Document`
+    ///
+    /// Like `validate_json`, with the output converted to a `Document`.
+    #[cfg(feature = "mongodb")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mongodb")))]
     pub fn validate(
         &self,
         form_data: &Value,
-    ) -> Result<Document, HashMap<String,Vec<ValidationError>>> {
-        let mut errors = HashMap::new();
-        let mut valid_data = HashMap::new();
+    ) -> Result<Document, ValidationErrors> {
+        let (valid_data, errors) = self.collect(form_data, self.coerce);
+        self.output_with(form_data, valid_data, errors, map_to_document)
+    }
 
-        for (field_name, validator) in &self.field_validators {
-            let value = if field_name.contains('.') {
-                let mut current = form_data;
-                for part in field_name.split('.') {
-                    current = current.get(part).unwrap_or(&Value::Null);
-                }
-                current
-            } else {
-                form_data.get(field_name).unwrap_or(&Value::Null)
-            };
-            let processed_value = if let Some(rules) = validator.as_any().downcast_ref::<Rules>() {
-                if value.is_null() && rules.default_value.is_some() {
-                    rules.default_value.as_ref().unwrap()
-                } else {
-                    value
+    /// Validates form data asynchronously with access to a data store
+    ///
+    /// Used for validators that require database checks (like uniqueness).
+    /// Any `Store` works, e.g. a `mongodb::Database`.
+    /// Returns either:
+    /// - Ok(Map) with validated values (including defaults)
+    /// - Err(AsyncValidationError::Validation) with field names and error lists
+    /// - Err(AsyncValidationError::Store) when the store failed, with the
+    ///   driver error as its source
+    ///
+    /// Up to `concurrency` fields are validated at the same time; errors and
+    /// output keep the order in which fields were added.
+    pub async fn validate_json_async(
+        &self,
+        store: &dyn Store,
+        form_data: &Value,
+    ) -> Result<Map<String, Value>, AsyncValidationError> {
+        self.validate_async_with(store, form_data, Ok).await
+    }
+
+    /// Validates form data asynchronously into a bson `Document`
+    ///
+    /// Like `validate_json_async`, with the output converted to a `Document`.
+    #[cfg(feature = "mongodb")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mongodb")))]
+    pub async fn validate_async(
+        &self,
+        store: &dyn Store,
+        form_data: &Value,
+    ) -> Result<Document, AsyncValidationError> {
+        self.validate_async_with(store, form_data, map_to_document).await
+    }
+
+    /// Validates a batch of records asynchronously, e.g. the rows of an import
+    ///
+    /// Lookups of `unique` and `exists` rules (including case-insensitive
+    /// ones) are coalesced into one `$in` query per collection and field
+    /// (using `Store::distinct`) instead of one query per record; stores that
+    /// don't implement `distinct` are queried with `count` per record instead.
+    /// Values of `unique` fields that repeat within the batch are also
+    /// rejected, except in the first record that has them.
+    ///
+    /// Returns one result per record, in order, like `validate_json_async`. If
+    /// a batched lookup fails, every record gets the store error. The form's
+    /// `timeout` applies to each record on its own, so a record that runs out
+    /// of time gets `AsyncValidationError::Timeout` without failing the others;
+    /// the batched lookups themselves are not limited by it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::FormValidator;
+    /// use validate_ro::rules::Rule;
+    /// use validate_ro::store::MemoryStore;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// let store = MemoryStore::new()
+    ///     .with_collection("categories", vec![json!({"slug": "books"})]);
+    /// let validator = FormValidator::new()
+    ///     .add("category", Rule::exists("categories", "slug"));
+    ///
+    /// let rows = [json!({"category": "books"}), json!({"category": "films"})];
+    /// let results = validator.validate_many_json(&store, &rows).await;
+    /// assert!(results[0].is_ok());
+    /// assert!(results[1].is_err());
+    /// # }
+    /// ```
+    pub async fn validate_many_json(
+        &self,
+        store: &dyn Store,
+        records: &[Value],
+    ) -> Vec<Result<Map<String, Value>, AsyncValidationError>> {
+        self.validate_many_with(store, records, Ok).await
+    }
+
+    /// Validates a batch of records asynchronously into bson `Document`s
+    ///
+    /// Like `validate_many_json`, with each output converted to a `Document`.
+    #[cfg(feature = "mongodb")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mongodb")))]
+    pub async fn validate_many(
+        &self,
+        store: &dyn Store,
+        records: &[Value],
+    ) -> Vec<Result<Document, AsyncValidationError>> {
+        self.validate_many_with(store, records, map_to_document).await
+    }
+
+    /// Validates a bson document synchronously, e.g. one read from MongoDB
+    ///
+    /// Validators see the document as relaxed extended JSON: ObjectIds,
+    /// DateTimes, Decimal128 and binary values appear as `{"$oid": ..}`,
+    /// `{"$date": ..}`, `{"$numberDecimal": ..}` and `{"$binary": ..}` and can
+    /// be checked with `Rule::object_id` and the other BSON type rules. The
+    /// output is a `Document` with these types restored; integers that were
+    /// Int64 in the input stay Int64. `Rule::int32` and `Rule::int64` also
+    /// reject integers of the other width.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use mongodb::bson::{doc, oid::ObjectId, DateTime};
+    /// use validate_ro::{rules, FormValidator};
+    /// use validate_ro::rules::Rule;
+    ///
+    /// let validator = FormValidator::new()
+    ///     .add("_id", rules![Rule::required(), Rule::object_id()])
+    ///     .add("created_at", Rule::datetime())
+    ///     .add("views", Rule::int64());
+    ///
+    /// let document = doc! {"_id": ObjectId::new(), "created_at": DateTime::now(), "views": 7_i64};
+    /// assert_eq!(validator.validate_bson(&document).unwrap(), document);
+    /// ```
+    #[cfg(feature = "mongodb")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mongodb")))]
+    pub fn validate_bson(
+        &self,
+        document: &Document,
+    ) -> Result<Document, ValidationErrors> {
+        let bson = Bson::Document(document.clone());
+        let form_data = bson.clone().into_relaxed_extjson();
+        let (mut valid_data, mut errors) = self.collect(&form_data, self.coerce);
+        self.check_int_widths(&form_data, &bson, "", &mut valid_data, &mut errors);
+        self.output_with(&form_data, valid_data, errors, |map| value_to_document(map, document))
+    }
+
+    /// Validates a bson document asynchronously with access to a data store
+    ///
+    /// Combines `validate_bson` and `validate_async`.
+    #[cfg(feature = "mongodb")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mongodb")))]
+    pub async fn validate_bson_async(
+        &self,
+        store: &dyn Store,
+        document: &Document,
+    ) -> Result<Document, AsyncValidationError> {
+        let bson = Bson::Document(document.clone());
+        let form_data = bson.clone().into_relaxed_extjson();
+        let collected = self.collect_async(store, &form_data, self.coerce);
+        let Some((mut valid_data, mut errors)) = with_timeout(self.timeout, collected).await else {
+            return Err(AsyncValidationError::Timeout);
+        };
+        self.check_int_widths(&form_data, &bson, "", &mut valid_data, &mut errors);
+        if let Some(e) = find_infrastructure_error(&errors) {
+            return Err(e);
+        }
+        self.output_with(&form_data, valid_data, errors, |map| value_to_document(map, document))
+            .map_err(AsyncValidationError::Validation)
+    }
+
+    /// Replaces every error of a field with a custom message
+    ///
+    /// Works like `Rules::message`, for all rules of the field. `field_name`
+    /// is the path given to `add`. Errors inside nested schemas are kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::{rules, FormValidator};
+    /// use validate_ro::rules::Rule;
+    ///
+    /// let validator = FormValidator::new()
+    ///     .add("password", rules![Rule::required(), Rule::min_length(8)])
+    ///     .message("password", "password must have at least 8 characters");
+    /// let errors = validator.validate_json(&json!({})).unwrap_err();
+    /// assert_eq!(errors["password"].len(), 1);
+    /// ```
+    pub fn message(mut self, field_name: &str, message: &str) -> FormValidator {
+        self.field_messages.insert(field_name.to_string(), message.to_string());
+        self
+    }
+
+    pub fn break_on_error(mut self) -> FormValidator {
+        self.break_on_error = true;
+        self
+    }
+
+    /// Limits how long `validate_json_async` may take as a whole
+    ///
+    /// When the limit is reached, validation is abandoned and
+    /// `AsyncValidationError::Timeout` is returned. Dropping the validation
+    /// future, on a timeout or otherwise, leaves nothing half-applied: results
+    /// are only assembled once every field is done. `validate_many_json` applies
+    /// the limit to each record.
+    pub fn timeout(mut self, limit: Duration) -> FormValidator {
+        self.timeout = Some(limit);
+        self
+    }
+
+    /// Sets how many fields `validate_json_async` validates at the same time
+    ///
+    /// Defaults to 10; 1 validates fields one after another. With
+    /// `break_on_error`, fields after the first failing one may already have
+    /// been checked, but their results are discarded.
+    pub fn concurrency(mut self, limit: usize) -> FormValidator {
+        self.concurrency = limit.max(1);
+        self
+    }
+
+    /// Sets how keys without registered rules are handled
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::{FormValidator, Strictness};
+    /// use validate_ro::rules::Rule;
+    ///
+    /// let validator = FormValidator::new()
+    ///     .strictness(Strictness::Reject)
+    ///     .add("name", Rule::required());
+    /// let errors = validator.validate_json(&json!({"name": "Ali", "admin": true})).unwrap_err();
+    /// assert!(errors.contains_key("admin"));
+    /// ```
+    pub fn strictness(mut self, strictness: Strictness) -> FormValidator {
+        self.strictness = strictness;
+        self
+    }
+
+    /// Converts string input of every field to the type declared by its type rule
+    ///
+    /// See `Rules::coerce` for the conversions.
+    pub fn coerce(mut self) -> FormValidator {
+        self.coerce = true;
+        self
+    }
+
+    /// Keeps validated nested fields as flat dotted keys in the output
+    ///
+    /// By default "profile.age" is returned as `{profile: {age: ..}}`; with this
+    /// option it is returned as `{"profile.age": ..}`, ready for a `$set` update.
+    pub fn flat_output(mut self) -> FormValidator {
+        self.flat_output = true;
+        self
+    }
+}
+
+impl FormValidator {
+    /// Validates every field and returns the valid values and the errors, both
+    /// keyed by concrete field path
+    fn collect(&self, form_data: &Value, coerce: bool) -> (IndexMap<String, Value>, ValidationErrors) {
+        let mut errors = ValidationErrors::new();
+        let mut valid_data = IndexMap::new();
+
+        'fields: for (field_name, validator) in &self.field_validators {
+            for (path, value) in resolve_path(form_data, field_name) {
+                if let Some(schema) = validator.as_any().downcast_ref::<FormValidator>() {
+                    let (nested_data, nested_errors) = match check_nested_type(value) {
+                        Ok(_) => schema.collect(value, coerce || schema.coerce),
+                        Err(err) => (IndexMap::new(), ValidationErrors::from_iter([(String::new(), vec![err])])),
+                    };
+                    let failed = !nested_errors.is_empty();
+                    merge_nested(&path, nested_data, nested_errors, &mut valid_data, &mut errors);
+                    if failed && self.break_on_error {
+                        break 'fields;
+                    }
+                    continue;
                 }
-            } else {
-                value
-            };
-            if let Err(err) = validator.validate(processed_value) {
-                match errors.get_mut(field_name){
-                    None => {
-                        errors.insert(field_name.clone(),vec![err]);
+
+                let ctx = Context::new(form_data, &path);
+                let message = self.field_messages.get(field_name);
+                match process_field(validator.as_ref(), value, &ctx, coerce, message) {
+                    Ok(processed_value) => {
+                        valid_data.insert(path, processed_value);
                     }
-                    Some(a) => {
-                        a.push(err);
+                    Err(field_errors) => {
+                        errors.add_all(path, field_errors);
+
+                        if self.break_on_error {
+                            break 'fields;
+                        }
                     }
                 }
+            }
+        }
 
-                if self.break_on_error {
-                    break;
-                }
-            } else {
-                valid_data.insert(field_name.clone(), processed_value.clone());
+        if !self.break_on_error || errors.is_empty() {
+            self.check_unknown_fields(form_data, &mut errors, &mut valid_data);
+        }
+
+        (valid_data, errors)
+    }
+
+    async fn collect_async(&self, store: &dyn Store, form_data: &Value, coerce: bool) -> (IndexMap<String, Value>, ValidationErrors) {
+        let mut errors = ValidationErrors::new();
+        let mut valid_data = IndexMap::new();
+
+        let mut fields = Vec::new();
+        for (field_name, validator) in &self.field_validators {
+            for (path, value) in resolve_path(form_data, field_name) {
+                let message = self.field_messages.get(field_name);
+                fields.push(collect_field_async(validator.as_ref(), store, form_data, path, value, coerce, message));
             }
         }
 
-        if errors.is_empty() {
-            match hashmap_to_document(valid_data){
-                Ok(a) => {
-                    Ok(a)
+        // `buffered` yields results in field order, whatever order they finish in
+        let mut outcomes = stream::iter(fields).buffered(self.concurrency);
+
+        while let Some((path, outcome)) = outcomes.next().await {
+            match outcome {
+                FieldOutcome::Nested(nested_data, nested_errors) => {
+                    let failed = !nested_errors.is_empty();
+                    merge_nested(&path, nested_data, nested_errors, &mut valid_data, &mut errors);
+                    if failed && self.break_on_error {
+                        break;
+                    }
                 }
-                Err(e) => {
-                    errors.insert("data".to_string(),vec![ValidationError::Custom(e.to_string())]);
-                    Err(errors)
+                FieldOutcome::Field(Ok(processed_value)) => {
+                    valid_data.insert(path, processed_value);
+                }
+                FieldOutcome::Field(Err(field_errors)) => {
+                    errors.add_all(path, field_errors);
+
+                    if self.break_on_error {
+                        break;
+                    }
                 }
             }
-        } else {
-            Err(errors)
         }
+
+        if !self.break_on_error || errors.is_empty() {
+            self.check_unknown_fields(form_data, &mut errors, &mut valid_data);
+        }
+
+        (valid_data, errors)
     }
 
-    /// Validates form data asynchronously with MongoDB access
-    ///
-    /// Used for validators that require database checks (like uniqueness)
-    /// Returns either:
-    /// - Ok(Document) with validated values (including defaults)
-    /// - Err(HashMap) with field names and error lists
-    pub async fn validate_async(
+    async fn validate_async_with<T>(
+        &self,
+        store: &dyn Store,
+        form_data: &Value,
+        convert: impl FnOnce(Map<String, Value>) -> Result<T, String>,
+    ) -> Result<T, AsyncValidationError> {
+        let collected = self.collect_async(store, form_data, self.coerce);
+        let Some((valid_data, errors)) = with_timeout(self.timeout, collected).await else {
+            return Err(AsyncValidationError::Timeout);
+        };
+        self.output_async(form_data, valid_data, errors, convert)
+    }
+
+    async fn validate_many_with<T>(
+        &self,
+        store: &dyn Store,
+        records: &[Value],
+        convert: impl Fn(Map<String, Value>) -> Result<T, String>,
+    ) -> Vec<Result<T, AsyncValidationError>> {
+        // Dry run to learn which lookups the batch needs
+        let recorder = RecordingStore::default();
+        for record in records {
+            with_timeout(self.timeout, self.collect_async(&recorder, record, self.coerce)).await;
+        }
+        let store = match PrefetchedStore::prefetch(store, recorder).await {
+            Ok(store) => store,
+            Err(e) => {
+                return records.iter().map(|_| Err(AsyncValidationError::Store(e.clone()))).collect();
+            }
+        };
+
+        let mut claimed = HashSet::new();
+        let mut results = Vec::with_capacity(records.len());
+        for record in records {
+            let collected = self.collect_async(&store, record, self.coerce);
+            let Some((mut valid_data, mut errors)) = with_timeout(self.timeout, collected).await else {
+                results.push(Err(AsyncValidationError::Timeout));
+                continue;
+            };
+            self.claim_unique_values(record, &mut valid_data, &mut errors, &mut claimed);
+            results.push(self.output_async(record, valid_data, errors, &convert));
+        }
+        results
+    }
+
+    fn output_async<T>(
         &self,
-        db:&Database,
         form_data: &Value,
-    ) -> Result<Document, HashMap<String,Vec<ValidationError>>> {
-        let mut errors = HashMap::new();
-        let mut valid_data = HashMap::new();
+        valid_data: IndexMap<String, Value>,
+        errors: ValidationErrors,
+        convert: impl FnOnce(Map<String, Value>) -> Result<T, String>,
+    ) -> Result<T, AsyncValidationError> {
+        if let Some(e) = find_infrastructure_error(&errors) {
+            return Err(e);
+        }
+        self.output_with(form_data, valid_data, errors, convert).map_err(AsyncValidationError::Validation)
+    }
 
+    /// Rejects values of `unique` fields already used by an earlier record of the batch
+    #[cfg(feature = "mongodb")]
+    fn claim_unique_values(
+        &self,
+        record: &Value,
+        valid_data: &mut IndexMap<String, Value>,
+        errors: &mut ValidationErrors,
+        claimed: &mut HashSet<String>,
+    ) {
         for (field_name, validator) in &self.field_validators {
-            let value = if field_name.contains('.') {
-                let mut current = form_data;
-                for part in field_name.split('.') {
-                    current = current.get(part).unwrap_or(&Value::Null);
-                }
-                current
-            } else {
-                form_data.get(field_name).unwrap_or(&Value::Null)
+            let unique: Vec<&UniqueValidator> = match validator.as_any().downcast_ref::<Rules>() {
+                Some(rules) => rules.validators()
+                    .filter_map(|validator| validator.as_any().downcast_ref::<UniqueValidator>())
+                    .collect(),
+                None => validator.as_any().downcast_ref::<UniqueValidator>().into_iter().collect(),
             };
-            let processed_value = if let Some(rules) = validator.as_any().downcast_ref::<Rules>() {
-                if value.is_null() && rules.default_value.is_some() {
-                    rules.default_value.as_ref().unwrap()
+            if unique.is_empty() {
+                continue;
+            }
+
+            for (path, _) in resolve_path(record, field_name) {
+                let Some(value) = valid_data.get(&path) else {
+                    continue;
+                };
+                let ctx = Context::new(record, &path);
+                let keys: Vec<String> = unique.iter().filter_map(|rule| rule.batch_key(value, &ctx)).collect();
+                if keys.iter().any(|key| claimed.contains(key)) {
+                    valid_data.shift_remove(&path);
+                    errors.add(path, ValidationError::UniqueError);
                 } else {
-                    value
+                    claimed.extend(keys);
                 }
-            } else {
-                value
-            };
+            }
+        }
+    }
 
-            if let Err(err) = validator.validate_async(db,processed_value).await {
-                match errors.get_mut(field_name){
-                    None => {
-                        errors.insert(field_name.clone(),vec![err]);
-                    }
-                    Some(a) => {
-                        a.push(err);
+    /// Rejects integers whose bson width doesn't match the `int32` or `int64`
+    /// rule of their field
+    ///
+    /// Validators see bson input as JSON, where Int32 and Int64 look the same,
+    /// so widths are checked against the original `bson` once the fields are
+    /// validated. `prefix` is the path of `form_data` within the whole form.
+    #[cfg(feature = "mongodb")]
+    fn check_int_widths(
+        &self,
+        form_data: &Value,
+        bson: &Bson,
+        prefix: &str,
+        valid_data: &mut IndexMap<String, Value>,
+        errors: &mut ValidationErrors,
+    ) {
+        for (field_name, validator) in &self.field_validators {
+            if let Some(schema) = validator.as_any().downcast_ref::<FormValidator>() {
+                for (path, value) in resolve_path(form_data, field_name) {
+                    if let Some(nested) = bson_at(bson, &path) {
+                        schema.check_int_widths(value, nested, &join_path(prefix, &path), valid_data, errors);
                     }
                 }
-                if self.break_on_error {
-                    break;
+                continue;
+            }
+            let rules = type_rules(validator.as_ref());
+            if rules.is_empty() {
+                continue;
+            }
+
+            for (path, _) in resolve_path(form_data, field_name) {
+                if self.break_on_error && !errors.is_empty() {
+                    return;
+                }
+                let full_path = join_path(prefix, &path);
+                let (Some(value), Some(original)) = (valid_data.get(&full_path), bson_at(bson, &path)) else {
+                    continue;
+                };
+                let error = rules.iter().find_map(|(rule, message)| {
+                    rule.check_width(value, original).err().map(|error| with_field_message(error, *message))
+                });
+                if let Some(error) = error {
+                    let error = with_field_message(error, self.field_messages.get(field_name));
+                    valid_data.shift_remove(&full_path);
+                    errors.add(full_path, error);
                 }
-            } else {
-                valid_data.insert(field_name.clone(), processed_value.clone());
             }
         }
+    }
+
+    /// `unique` rules need the `mongodb` feature, so there is nothing to claim
+    #[cfg(not(feature = "mongodb"))]
+    fn claim_unique_values(
+        &self,
+        _record: &Value,
+        _valid_data: &mut IndexMap<String, Value>,
+        _errors: &mut ValidationErrors,
+        _claimed: &mut HashSet<String>,
+    ) {
+    }
 
+    /// Nests the validated values following the shape of `form_data` and
+    /// converts them with `convert`
+    fn output_with<T>(
+        &self,
+        form_data: &Value,
+        valid_data: IndexMap<String, Value>,
+        mut errors: ValidationErrors,
+        convert: impl FnOnce(Map<String, Value>) -> Result<T, String>,
+    ) -> Result<T, ValidationErrors> {
         if errors.is_empty() {
-            match hashmap_to_document(valid_data){
+            match convert(nest_fields(form_data, valid_data, self.flat_output)) {
                 Ok(a) => {
                     Ok(a)
                 }
                 Err(e) => {
-                    errors.insert("data".to_string(),vec![ValidationError::Custom(e.to_string())]);
+                    errors.add_form_error(ValidationError::Custom(e));
                     Err(errors)
                 }
             }
@@ -316,19 +1104,440 @@ impl FormValidator {
         }
     }
 
-    pub fn break_on_error(mut self) -> FormValidator {
-        self.break_on_error = true;
+    /// Reports or copies keys of `form_data` that no registered field covers
+    fn check_unknown_fields(
+        &self,
+        form_data: &Value,
+        errors: &mut ValidationErrors,
+        valid_data: &mut IndexMap<String, Value>,
+    ) {
+        if self.strictness == Strictness::Strip {
+            return;
+        }
+        let patterns: Vec<Vec<&str>> = self.field_validators.keys()
+            .map(|field_name| field_name.split('.').collect())
+            .collect();
+        let mut unknown = Vec::new();
+        collect_unknown_fields(&patterns, form_data, &mut Vec::new(), &mut unknown);
+
+        for (parts, value) in unknown {
+            // A key with a dot can't be told apart from a nested path (it would
+            // collide with "profile.age" for {"profile.age": ..}), so it is
+            // never copied and is reported with its dots escaped
+            let dotted = parts.iter().any(|part| part.contains('.'));
+            match self.strictness {
+                Strictness::Reject => {
+                    let path: Vec<String> = parts.iter().map(|part| escape_path_segment(part)).collect();
+                    errors.add(path.join("."), ValidationError::UnknownField);
+                    if self.break_on_error {
+                        break;
+                    }
+                }
+                _ if dotted => {}
+                _ => {
+                    // Never replaces a validated value
+                    valid_data.entry(parts.join(".")).or_insert_with(|| value.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Validates one concrete field path of `form_data` for `collect_async`
+async fn collect_field_async(
+    validator: &(dyn Validator + Send + Sync),
+    store: &dyn Store,
+    form_data: &Value,
+    path: String,
+    value: &Value,
+    coerce: bool,
+    message: Option<&String>,
+) -> (String, FieldOutcome) {
+    if let Some(schema) = validator.as_any().downcast_ref::<FormValidator>() {
+        let (nested_data, nested_errors) = match check_nested_type(value) {
+            Ok(_) => Box::pin(schema.collect_async(store, value, coerce || schema.coerce)).await,
+            Err(err) => (IndexMap::new(), ValidationErrors::from_iter([(String::new(), vec![err])])),
+        };
+        return (path, FieldOutcome::Nested(nested_data, nested_errors));
+    }
+
+    let ctx = Context::new(form_data, &path);
+    let result = process_field_async(validator, store, value, &ctx, coerce).await;
+    (path, FieldOutcome::Field(apply_field_message(result, message)))
+}
+
+/// Result of validating one field path in `collect_async`
+enum FieldOutcome {
+    Field(Result<Value, Vec<ValidationError>>),
+    Nested(IndexMap<String, Value>, ValidationErrors),
+}
+
+/// Lets a `FormValidator` be used as a schema for another form's field
+///
+/// Added directly to a `FormValidator` (e.g. `.add("address", address_validator)`
+/// or `.add("line_items.*", item_validator)`), nested errors are reported under
+/// prefixed paths such as "address.zip" or "line_items.2.qty". Used anywhere
+/// else, the errors are returned as a single `ValidationError::Nested`; in a
+/// `Rules` chain the validated object (without unknown keys, unless the
+/// schema passes them through) also becomes the field's value.
+#[async_trait]
+impl Validator for FormValidator {
+    fn validate(&self, value: &Value) -> ValidationResult {
+        check_nested_type(value)?;
+        let (_, errors) = self.collect(value, self.coerce);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError::Nested(errors))
+        }
+    }
+
+    async fn validate_async(&self, store: &dyn Store, value: &Value) -> ValidationResult {
+        check_nested_type(value)?;
+        let (_, errors) = self.collect_async(store, value, self.coerce).await;
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError::Nested(errors))
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
         self
     }
 }
-fn hashmap_to_document(input: HashMap<String, Value>) -> Result<Document, bson::ser::Error> {
-    let mut doc = Document::new();
 
-    for (key, value) in input {
-        let bson_value = to_bson(&value)?;
+impl Default for FormValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Validates a field value and returns the value to store in the output
+///
+/// `Rules` apply their default value, coercion and transformers; any other
+/// validator keeps the input value as is (coerced when it is a type rule).
+fn process_field(validator: &(dyn Validator + Send + Sync), value: &Value, ctx: &Context<'_>, coerce: bool, message: Option<&String>) -> Result<Value, Vec<ValidationError>> {
+    let result = match validator.as_any().downcast_ref::<Rules>() {
+        Some(rules) => rules.process_with_context(value, Some(ctx), coerce),
+        None => {
+            let value = coerce_value(validator, value, coerce);
+            validator.validate_with_context(&value, ctx).map(|_| value).map_err(|err| vec![err])
+        }
+    };
+    apply_field_message(result, message)
+}
+
+/// Type rules of a field's validator, with the message replacing their errors
+#[cfg(feature = "mongodb")]
+fn type_rules(validator: &(dyn Validator + Send + Sync)) -> Vec<(&TypeValidator, Option<&String>)> {
+    match validator.as_any().downcast_ref::<Rules>() {
+        Some(rules) => rules.steps.iter()
+            .filter_map(|step| match step {
+                Step::Validate(validator, message) => {
+                    validator.as_any().downcast_ref::<TypeValidator>().map(|rule| (rule, message.as_ref()))
+                }
+                Step::Transform(_) => None,
+            })
+            .collect(),
+        None => validator.as_any().downcast_ref::<TypeValidator>().map(|rule| (rule, None)).into_iter().collect(),
+    }
+}
+
+#[cfg(feature = "mongodb")]
+fn with_field_message(error: ValidationError, message: Option<&String>) -> ValidationError {
+    match message {
+        Some(message) => error.with_message(message),
+        None => error,
+    }
+}
+
+/// Replaces the errors of a field with its `FormValidator::message`
+fn apply_field_message(result: Result<Value, Vec<ValidationError>>, message: Option<&String>) -> Result<Value, Vec<ValidationError>> {
+    let Some(message) = message else {
+        return result;
+    };
+    result.map_err(|errors| errors.into_iter().map(|error| error.with_message(message)).collect())
+}
+
+async fn process_field_async(validator: &(dyn Validator + Send + Sync), store: &dyn Store, value: &Value, ctx: &Context<'_>, coerce: bool) -> Result<Value, Vec<ValidationError>> {
+    if let Some(rules) = validator.as_any().downcast_ref::<Rules>() {
+        return rules.process_async_with_context(store, value, Some(ctx), coerce).await;
+    }
+    let value = coerce_value(validator, value, coerce);
+    validator.validate_async_with_context(store, &value, ctx).await.map(|_| value).map_err(|err| vec![err])
+}
+
+fn coerce_value(validator: &(dyn Validator + Send + Sync), value: &Value, coerce: bool) -> Value {
+    match validator.as_any().downcast_ref::<TypeValidator>() {
+        Some(type_rule) if coerce => type_rule.coerce(value.clone()),
+        _ => value.clone(),
+    }
+}
+
+/// Nested schemas validate objects; null is validated as an empty object
+fn check_nested_type(value: &Value) -> ValidationResult {
+    if value.is_null() || value.is_object() {
+        Ok(())
+    } else {
+        Err(ValidationError::TypeError {
+            expected: "object".to_string(),
+            got: value.to_string(),
+        })
+    }
+}
+
+/// Moves the results of a nested schema into the parent's, prefixing each
+/// path with the path of the nested field
+fn merge_nested(
+    prefix: &str,
+    nested_data: IndexMap<String, Value>,
+    nested_errors: ValidationErrors,
+    valid_data: &mut IndexMap<String, Value>,
+    errors: &mut ValidationErrors,
+) {
+    for (path, value) in nested_data {
+        valid_data.insert(join_path(prefix, &path), value);
+    }
+    errors.merge_prefixed(prefix, nested_errors);
+}
+
+/// Walks `value` and collects the paths not covered by any field pattern, as
+/// lists of keys
+///
+/// A key is covered when a pattern matches it or one of its parents, and is
+/// descended into when it is a parent of some pattern.
+fn collect_unknown_fields<'a>(
+    patterns: &[Vec<&str>],
+    value: &'a Value,
+    path: &mut Vec<String>,
+    unknown: &mut Vec<(Vec<String>, &'a Value)>,
+) {
+    let children: Vec<(String, &Value)> = match value {
+        Value::Object(map) => map.iter().map(|(key, child)| (key.clone(), child)).collect(),
+        Value::Array(items) => items.iter().enumerate().map(|(index, child)| (index.to_string(), child)).collect(),
+        _ => return,
+    };
+
+    for (key, child) in children {
+        path.push(key);
+        let matches = |pattern: &[&str], parts: &[String]| {
+            pattern.iter().zip(parts).all(|(p, part)| *p == part || (*p == "*" && part.parse::<usize>().is_ok()))
+        };
+        let covered = patterns.iter()
+            .any(|pattern| pattern.len() <= path.len() && matches(pattern, &path[..pattern.len()]));
+        if !covered {
+            let is_parent = patterns.iter()
+                .any(|pattern| pattern.len() > path.len() && matches(&pattern[..path.len()], path));
+            if is_parent {
+                collect_unknown_fields(patterns, child, path, unknown);
+            } else {
+                unknown.push((path.clone(), child));
+            }
+        }
+        path.pop();
+    }
+}
+
+/// Resolves a dotted field path against `data`
+///
+/// A `*` segment fans out over every element of an array and a numeric segment
+/// selects a single element. Each match is returned with its concrete path
+/// (e.g. "items.*.sku" -> "items.0.sku", "items.1.sku"). Missing keys resolve to
+/// null, while a wildcard over a missing or non-array value matches nothing.
+fn resolve_path<'a>(data: &'a Value, field_name: &str) -> Vec<(String, &'a Value)> {
+    let mut matches = vec![(String::new(), data)];
+
+    for part in field_name.split('.') {
+        let mut next = Vec::new();
+        for (path, current) in matches {
+            if part == "*" {
+                if let Value::Array(items) = current {
+                    for (index, item) in items.iter().enumerate() {
+                        next.push((join_path(&path, &index.to_string()), item));
+                    }
+                }
+                continue;
+            }
+            let value = match (current, part.parse::<usize>()) {
+                (Value::Array(items), Ok(index)) => items.get(index),
+                _ => current.get(part),
+            };
+            next.push((join_path(&path, part), value.unwrap_or(&Value::Null)));
+        }
+        matches = next;
+    }
+
+    matches
+}
+
+/// First store failure or timeout among `errors`, including those of nested forms
+fn find_infrastructure_error(errors: &ValidationErrors) -> Option<AsyncValidationError> {
+    errors.iter().flat_map(|(_, errors)| errors).find_map(|error| match error {
+        ValidationError::Store(e) => Some(AsyncValidationError::Store(e.clone())),
+        ValidationError::Timeout => Some(AsyncValidationError::Timeout),
+        ValidationError::Nested(nested) => find_infrastructure_error(nested),
+        _ => None,
+    })
+}
+
+/// Runs `future`, giving up with `None` once `limit` has passed
+async fn with_timeout<F: Future>(limit: Option<Duration>, future: F) -> Option<F::Output> {
+    let Some(limit) = limit else {
+        return Some(future.await);
+    };
+    match select(pin!(future), Delay::new(limit)).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(_) => None,
+    }
+}
+
+fn join_path(prefix: &str, part: &str) -> String {
+    if prefix.is_empty() || part.is_empty() {
+        format!("{}{}", prefix, part)
+    } else {
+        format!("{}.{}", prefix, part)
+    }
+}
 
-        doc.insert(key, bson_value);
+/// Lays out validated values by path, or keeps their dotted keys when `flat`
+///
+/// Containers follow the shape of `source`, the validated input: a numeric
+/// segment is an array index only where `source` has an array, and an object
+/// key everywhere else.
+fn nest_fields(source: &Value, input: IndexMap<String, Value>, flat: bool) -> Map<String, Value> {
+    if flat {
+        return input.into_iter().collect();
     }
 
-    Ok(doc)
-}
\ No newline at end of file
+    // Lay out every path in field order first, so that the values written
+    // below keep the position of their field
+    let mut root = Value::Object(Map::new());
+    for key in input.keys() {
+        let parts: Vec<&str> = key.split('.').collect();
+        insert_path(&mut root, Some(source), &parts, None);
+    }
+
+    // Shallow paths go first so that a whole-object field never overwrites
+    // values already placed inside it by one of its sub-paths
+    let mut entries: Vec<(String, Value)> = input.into_iter().collect();
+    entries.sort_by_key(|(key, _)| key.split('.').count());
+
+    for (key, value) in entries {
+        let parts: Vec<&str> = key.split('.').collect();
+        insert_path(&mut root, Some(source), &parts, Some(value));
+    }
+
+    match root {
+        Value::Object(map) => map,
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(feature = "mongodb")]
+fn map_to_document(map: Map<String, Value>) -> Result<Document, String> {
+    mongodb::bson::to_document(&map).map_err(|e| e.to_string())
+}
+
+/// Parses validated extended JSON back into a document, restoring the Int64
+/// type of values that were Int64 in `original`
+#[cfg(feature = "mongodb")]
+fn value_to_document(map: Map<String, Value>, original: &Document) -> Result<Document, String> {
+    let mut document = match Bson::try_from(Value::Object(map)) {
+        Ok(Bson::Document(document)) => document,
+        Ok(other) => return Err(format!("expected a document, got {}", other)),
+        Err(e) => return Err(e.to_string()),
+    };
+    for (key, value) in document.iter_mut() {
+        if let Some(original) = original.get(key) {
+            restore_int64(value, original);
+        }
+    }
+    Ok(document)
+}
+
+/// Value at a dotted path of a bson document
+#[cfg(feature = "mongodb")]
+fn bson_at<'a>(bson: &'a Bson, path: &str) -> Option<&'a Bson> {
+    let mut current = bson;
+    for part in path.split('.').filter(|part| !part.is_empty()) {
+        current = match (current, part.parse::<usize>()) {
+            (Bson::Array(items), Ok(index)) => items.get(index)?,
+            (Bson::Document(doc), _) => doc.get(part)?,
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
+/// Turns Int32 values of `value` back into Int64 where `original` had the
+/// same number as an Int64
+#[cfg(feature = "mongodb")]
+fn restore_int64(value: &mut Bson, original: &Bson) {
+    match (value, original) {
+        (value @ Bson::Int32(_), Bson::Int64(original)) if value.as_i32().map(i64::from) == Some(*original) => {
+            *value = Bson::Int64(*original);
+        }
+        (Bson::Document(doc), Bson::Document(original)) => {
+            for (key, value) in doc.iter_mut() {
+                if let Some(original) = original.get(key) {
+                    restore_int64(value, original);
+                }
+            }
+        }
+        (Bson::Array(items), Bson::Array(original)) => {
+            for (value, original) in items.iter_mut().zip(original) {
+                restore_int64(value, original);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Inserts `value` at the given path segments, creating intermediate objects
+/// (or arrays where `source` has an array) as needed. Without a value only the
+/// path is created, with null at its end. Indexes past the end of an array of
+/// `source` are skipped with the rest of their path.
+///
+/// A path never replaces a value other than null or a container, so a
+/// validated scalar is kept over the values of its sub-paths.
+fn insert_path(target: &mut Value, source: Option<&Value>, parts: &[&str], value: Option<Value>) {
+    let Some((part, rest)) = parts.split_first() else {
+        if let Some(value) = value {
+            *target = value;
+        }
+        return;
+    };
+    let index = match source {
+        Some(Value::Array(items)) => match part.parse::<usize>() {
+            // Slots the input doesn't have are never made up, so a path like
+            // "items.5.sku" on a one-element array is left out
+            Ok(index) if index >= items.len() => return,
+            index => index.ok(),
+        },
+        _ => None,
+    };
+    if target.is_null() {
+        *target = match index {
+            Some(_) => Value::Array(Vec::new()),
+            None => Value::Object(Map::new()),
+        };
+    }
+    let slot = match (target, index) {
+        (Value::Array(items), Some(index)) => {
+            // Indexes are within the input's own array
+            if items.len() <= index {
+                items.resize(index + 1, Value::Null);
+            }
+            &mut items[index]
+        }
+        (Value::Object(map), _) => map.entry(*part).or_insert(Value::Null),
+        _ => return,
+    };
+    let source = match (source, index) {
+        (Some(Value::Array(items)), Some(index)) => items.get(index),
+        (Some(Value::Object(map)), _) => map.get(*part),
+        _ => None,
+    };
+    insert_path(slot, source, rest, value);
+}
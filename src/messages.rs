@@ -0,0 +1,268 @@
+//! # Error Messages
+//!
+//! Renders `ValidationError`s into human-readable sentences from a catalog of
+//! templates keyed by error code (see `ValidationError::code`). Templates use
+//! `:name` placeholders: `:field` for the field and the error's parameters
+//! (see `ValidationError::params`), e.g. ":field must be at least :min characters".
+//!
+//! English is built in; catalogs for other locales are loaded from JSON (or
+//! TOML, with the `toml` feature) files mapping codes to templates. Codes
+//! missing from a loaded catalog fall back to English.
+//!
+//! ## Example
+//!
+//! ```
+//! use serde_json::json;
+//! use validate_ro::{rules, FormValidator};
+//! use validate_ro::messages::Messages;
+//! use validate_ro::rules::Rule;
+//!
+//! let validator = FormValidator::new()
+//!     .add("password", rules![Rule::required(), Rule::min_length(8)]);
+//! let errors = validator.validate_json(&json!({"password": "abc"})).unwrap_err();
+//!
+//! let messages = Messages::english().render_all(&errors);
+//! assert_eq!(messages["password"], ["password must be at least 8 characters"]);
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+use indexmap::IndexMap;
+use crate::error::{path_segments, ValidationError, ValidationErrors};
+
+const ENGLISH: &[(&str, &str)] = &[
+    ("required_error", ":field is required"),
+    ("type_error", ":field must be of type :expected"),
+    ("len_error", ":field must have a length of :length"),
+    ("min_len_error", ":field must be at least :min characters"),
+    ("max_len_error", ":field must not be longer than :max characters"),
+    ("eq_error", ":field must be equal to :value"),
+    ("min_error", ":field must be at least :min"),
+    ("max_error", ":field must not be greater than :max"),
+    ("numeric_error", ":field must be a number"),
+    ("accepted_error", ":field must be accepted"),
+    ("email_error", ":field must be a valid email address"),
+    ("email_domain_name_error", ":field must use an allowed email domain"),
+    ("in_error", ":field must be one of :values"),
+    ("not_in_error", ":field must not be one of :values"),
+    ("regex_error", ":field format is invalid"),
+    ("url_error", ":field must be a valid URL"),
+    ("ip_error", ":field must be a valid IP address"),
+    ("extension_error", ":field must have one of the extensions :extensions"),
+    ("unique_error", ":field has already been taken"),
+    ("exists_error", ":field does not exist"),
+    ("file_size_error", ":field size must be between :min and :max"),
+    ("same_error", ":field must match :other"),
+    ("different_error", ":field must be different from :other"),
+    ("confirmed_error", ":field confirmation does not match"),
+    ("gt_field_error", ":field must be greater than :other"),
+    ("lt_field_error", ":field must be less than :other"),
+    ("required_if_error", ":field is required when :other is :value"),
+    ("required_unless_error", ":field is required unless :other is :value"),
+    ("required_with_error", ":field is required when :others is present"),
+    ("required_with_all_error", ":field is required when :others are present"),
+    ("required_without_error", ":field is required when :others is not present"),
+    ("prohibited_if_error", ":field is prohibited when :other is :value"),
+    ("unknown_field_error", ":field is not allowed"),
+    ("nested_error", ":field is invalid"),
+    ("store_error", ":field could not be checked"),
+    ("timeout_error", ":field could not be checked in time"),
+    ("validate_error", ":message"),
+];
+
+/// Catalog of message templates for one locale
+///
+/// # Example
+///
+/// ```
+/// use validate_ro::error::ValidationError;
+/// use validate_ro::messages::Messages;
+///
+/// let messages = Messages::from_json(r#"{"required_error": ":field est obligatoire"}"#)
+///     .unwrap()
+///     .attribute("user.email", "adresse e-mail");
+/// assert_eq!(messages.render("user.email", &ValidationError::Required), "adresse e-mail est obligatoire");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Messages {
+    templates: HashMap<String, String>,
+    attributes: HashMap<String, String>,
+}
+
+impl Messages {
+    /// Built-in English catalog
+    pub fn english() -> Self {
+        Self {
+            templates: ENGLISH.iter().map(|(code, template)| (code.to_string(), template.to_string())).collect(),
+            attributes: HashMap::new(),
+        }
+    }
+
+    /// Catalog from a JSON object mapping error codes to templates
+    pub fn from_json(json: &str) -> Result<Self, CatalogError> {
+        let templates: HashMap<String, String> = serde_json::from_str(json).map_err(CatalogError::Json)?;
+        Ok(Self::english().extend(templates))
+    }
+
+    /// Catalog from a TOML table mapping error codes to templates
+    #[cfg(feature = "toml")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "toml")))]
+    pub fn from_toml(toml: &str) -> Result<Self, CatalogError> {
+        let templates: HashMap<String, String> = toml::from_str(toml).map_err(CatalogError::Toml)?;
+        Ok(Self::english().extend(templates))
+    }
+
+    /// Loads a catalog file, choosing the format by its extension
+    ///
+    /// `.json` files are always supported, `.toml` files with the `toml` feature.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CatalogError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(CatalogError::Io)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json(&content),
+            #[cfg(feature = "toml")]
+            Some("toml") => Self::from_toml(&content),
+            _ => Err(CatalogError::UnsupportedFormat(path.display().to_string())),
+        }
+    }
+
+    /// Sets the template of an error code
+    pub fn set(mut self, code: &str, template: &str) -> Self {
+        self.templates.insert(code.to_string(), template.to_string());
+        self
+    }
+
+    /// Sets the name `:field` is replaced with for a field path
+    ///
+    /// Without one, the last named segment of the path is used with
+    /// underscores replaced by spaces ("items.0.unit_price" becomes "unit price").
+    pub fn attribute(mut self, field: &str, name: &str) -> Self {
+        self.attributes.insert(field.to_string(), name.to_string());
+        self
+    }
+
+    /// Renders one error of a field
+    ///
+    /// Errors without a template are rendered as their code.
+    /// Errors with a custom message (see `Rules::message`) use the template
+    /// of that key if the catalog has one, and the message itself otherwise.
+    pub fn render(&self, field: &str, error: &ValidationError) -> String {
+        let template = match error {
+            ValidationError::Message { message, .. } => self.templates.get(message).unwrap_or(message),
+            _ => match self.templates.get(error.code()) {
+                Some(template) => template,
+                None => return error.code().to_string(),
+            },
+        };
+
+        fill(template, &self.attribute_name(field), error)
+    }
+
+    /// Renders every error of a form, keyed by field path
+    ///
+    /// Errors of nested forms are listed under their prefixed paths.
+    pub fn render_all(&self, errors: &ValidationErrors) -> IndexMap<String, Vec<String>> {
+        let mut messages: IndexMap<String, Vec<String>> = IndexMap::new();
+        for (path, error) in errors.flatten() {
+            let message = self.render(&path, error);
+            messages.entry(path).or_default().push(message);
+        }
+        messages
+    }
+
+    fn extend(mut self, templates: HashMap<String, String>) -> Self {
+        self.templates.extend(templates);
+        self
+    }
+
+    fn attribute_name(&self, field: &str) -> String {
+        if let Some(name) = self.attributes.get(field) {
+            return name.clone();
+        }
+        let segments = path_segments(field);
+        segments
+            .iter()
+            .rev()
+            .find(|part| part.parse::<usize>().is_err())
+            .map_or(field, String::as_str)
+            .replace('_', " ")
+    }
+}
+
+/// Built-in English template of an error code
+pub(crate) fn english_template(code: &str) -> Option<&'static str> {
+    ENGLISH.iter().find(|(c, _)| *c == code).map(|(_, template)| *template)
+}
+
+/// Replaces the `:field` and parameter placeholders of a template
+///
+/// The template is scanned once, so placeholders inside substituted values
+/// (e.g. a field named ":min") are left as they are.
+pub(crate) fn fill(template: &str, field: &str, error: &ValidationError) -> String {
+    let mut params = error.params();
+    params.push(("field", field.to_string()));
+    // Longer names first, so that ":min" doesn't match the start of ":minimum"
+    params.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+
+    let mut message = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find(':') {
+        message.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match params.iter().find(|(name, _)| after.starts_with(name)) {
+            Some((name, value)) => {
+                message.push_str(value);
+                rest = &after[name.len()..];
+            }
+            None => {
+                message.push(':');
+                rest = after;
+            }
+        }
+    }
+    message.push_str(rest);
+    message
+}
+
+impl Default for Messages {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+/// Failure to load a message catalog
+#[derive(Debug)]
+pub enum CatalogError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    #[cfg(feature = "toml")]
+    Toml(toml::de::Error),
+    /// The file extension is not a supported catalog format
+    UnsupportedFormat(String),
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatalogError::Io(e) => write!(f, "cannot read message catalog: {}", e),
+            CatalogError::Json(e) => write!(f, "invalid JSON message catalog: {}", e),
+            #[cfg(feature = "toml")]
+            CatalogError::Toml(e) => write!(f, "invalid TOML message catalog: {}", e),
+            CatalogError::UnsupportedFormat(path) => write!(f, "unsupported message catalog format: {}", path),
+        }
+    }
+}
+
+impl Error for CatalogError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CatalogError::Io(e) => Some(e),
+            CatalogError::Json(e) => Some(e),
+            #[cfg(feature = "toml")]
+            CatalogError::Toml(e) => Some(e),
+            CatalogError::UnsupportedFormat(_) => None,
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! # Problem Details
+//!
+//! Renders validation failures as RFC 7807 Problem Details documents, to be
+//! returned with the `application/problem+json` content type. Errors are
+//! listed in an `errors` extension member keyed by the JSON Pointer of each
+//! field (or its dotted path, see `PathStyle`), each with its error code and
+//! message:
+//!
+//! ```json
+//! {
+//!   "type": "about:blank",
+//!   "title": "Unprocessable Content",
+//!   "status": 422,
+//!   "errors": {
+//!     "/items/0/qty": [{"code": "min_error", "detail": "qty must be at least 1"}]
+//!   }
+//! }
+//! ```
+//!
+//! ## Example
+//!
+//! ```
+//! use serde_json::json;
+//! use validate_ro::{rules, FormValidator};
+//! use validate_ro::problem::ProblemDetails;
+//! use validate_ro::rules::Rule;
+//!
+//! let validator = FormValidator::new()
+//!     .add("password", rules![Rule::required(), Rule::min_length(8)]);
+//! let errors = validator.validate_json(&json!({"password": "abc"})).unwrap_err();
+//!
+//! let problem = ProblemDetails::new()
+//!     .type_uri("https://example.com/problems/validation")
+//!     .title("Your request is not valid")
+//!     .render(&errors);
+//! assert_eq!(problem["status"], 422);
+//! assert_eq!(problem["errors"]["/password"][0]["detail"], "password must be at least 8 characters");
+//! ```
+
+use indexmap::IndexMap;
+use serde_json::{json, Map, Value};
+use crate::error::{path_segments, ValidationError, ValidationErrors};
+use crate::messages::Messages;
+
+/// Media type of Problem Details documents
+pub const CONTENT_TYPE: &str = "application/problem+json";
+
+/// How field paths are written as keys of the `errors` member
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathStyle {
+    /// JSON Pointer (RFC 6901), e.g. "/items/0/qty"; form-level errors use
+    /// the empty pointer ""
+    #[default]
+    Pointer,
+    /// Dotted path as reported by `FormValidator`, e.g. "items.0.qty"
+    Dotted,
+}
+
+/// Renderer of validation failures as Problem Details documents
+///
+/// Defaults to type "about:blank", title "Unprocessable Content", status 422,
+/// JSON Pointer keys and English messages.
+#[derive(Debug, Clone)]
+pub struct ProblemDetails {
+    type_uri: String,
+    title: String,
+    status: u16,
+    detail: Option<String>,
+    path_style: PathStyle,
+    messages: Messages,
+}
+
+impl ProblemDetails {
+    pub fn new() -> Self {
+        Self {
+            type_uri: "about:blank".to_string(),
+            title: "Unprocessable Content".to_string(),
+            status: 422,
+            detail: None,
+            path_style: PathStyle::Pointer,
+            messages: Messages::english(),
+        }
+    }
+
+    /// Sets the `type` member, a URI identifying the problem type
+    pub fn type_uri(mut self, uri: &str) -> Self {
+        self.type_uri = uri.to_string();
+        self
+    }
+
+    /// Sets the `title` member, a short summary of the problem type
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    /// Sets the `status` member, the HTTP status code of the response
+    pub fn status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Sets the `detail` member, omitted by default
+    pub fn detail(mut self, detail: &str) -> Self {
+        self.detail = Some(detail.to_string());
+        self
+    }
+
+    pub fn path_style(mut self, style: PathStyle) -> Self {
+        self.path_style = style;
+        self
+    }
+
+    /// Sets the catalog the error messages are rendered with
+    pub fn messages(mut self, messages: Messages) -> Self {
+        self.messages = messages;
+        self
+    }
+
+    /// Renders the errors of a form into a Problem Details document
+    ///
+    /// Errors of nested forms are listed under their own paths. Errors with a
+    /// custom message keep the code of the error they replace.
+    pub fn render(&self, errors: &ValidationErrors) -> Value {
+        let mut fields: IndexMap<String, Vec<Value>> = IndexMap::new();
+        for (path, error) in errors.flatten() {
+            // A custom message only replaces the detail, not the code clients branch on
+            let code = match error {
+                ValidationError::Message { error, .. } => error.code(),
+                _ => error.code(),
+            };
+            let entry = json!({
+                "code": code,
+                "detail": self.messages.render(&path, error),
+            });
+            let key = match self.path_style {
+                PathStyle::Pointer => json_pointer(&path),
+                PathStyle::Dotted => path,
+            };
+            fields.entry(key).or_default().push(entry);
+        }
+
+        let mut problem = Map::new();
+        problem.insert("type".to_string(), json!(self.type_uri));
+        problem.insert("title".to_string(), json!(self.title));
+        problem.insert("status".to_string(), json!(self.status));
+        if let Some(detail) = &self.detail {
+            problem.insert("detail".to_string(), json!(detail));
+        }
+        problem.insert("errors".to_string(), json!(fields));
+        Value::Object(problem)
+    }
+}
+
+impl Default for ProblemDetails {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// JSON Pointer of a dotted field path, escaping "~" and "/" in segments
+/// and unescaping dots
+fn json_pointer(path: &str) -> String {
+    if path.is_empty() {
+        return String::new();
+    }
+    path_segments(path)
+        .iter()
+        .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
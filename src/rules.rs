@@ -11,6 +11,14 @@
 //! - `array()` - Value must be an array
 //! - `object()` - Value must be an object
 //!
+//! ## BSON Type Validation (requires the `mongodb` feature)
+//! - `int32()` / `int64()` - Value must be an integer in the Int32/Int64 range
+//!   (and of that width in bson input)
+//! - `object_id()` - Value must be an ObjectId
+//! - `datetime()` - Value must be a DateTime
+//! - `decimal128()` - Value must be a Decimal128
+//! - `binary()` - Value must be binary data
+//!
 //! ## String Validation
 //! - `length(n)` - Exact length
 //! - `min_length(n)` - Minimum length
@@ -29,22 +37,44 @@
 //! - `in_values()` - Value must be in allowed set
 //! - `not_in_values()` - Value must not be in excluded set
 //!
+//! ## Conditional Presence
+//! - `required_if(field, value)` - Required when another field equals a value
+//! - `required_unless(field, value)` - Required unless another field equals a value
+//! - `required_with(fields)` - Required when any of the fields is present
+//! - `required_with_all(fields)` - Required when all of the fields are present
+//! - `required_without(fields)` - Required when any of the fields is missing
+//! - `prohibited_if(field, value)` - Must be missing when another field equals a value
+//!
+//! ## Cross-field Validation
+//! - `same(field)` - Value must equal another field
+//! - `different(field)` - Value must differ from another field
+//! - `confirmed()` - Value must equal its `<field>_confirmation` field
+//! - `gt_field(field)` - Value must be greater than another field
+//! - `lt_field(field)` - Value must be less than another field
+//!
 //! ## Database Validation
-//! - `unique()` - Field value must be unique in MongoDB collection
+//! - `unique()` - Field value must be unique in a collection of the data store
+//!   (requires the `mongodb` feature)
+//! - `exists()` - Field value must reference an existing document
 //!
 //! ## Custom Validation
 //! - `custom()` - Implement custom validation logic
 
 use std::any::Any;
+use std::cmp::Ordering;
 use std::collections::HashSet;
 use async_trait::async_trait;
-use mongodb::bson::{doc, Bson, Document};
-use mongodb::{Collection, Database};
-use mongodb::bson::oid::ObjectId;
+#[cfg(feature = "mongodb")]
+use mongodb::bson::{oid::ObjectId, Bson};
 use regex::Regex;
-use serde_json::Value;
+use serde_json::{Number, Value};
 use crate::error::ValidationError;
-use crate::traits::{ValidationResult, Validator};
+use crate::store::{Filter, Query, Store};
+#[cfg(feature = "mongodb")]
+use crate::store::DEFAULT_COLLATION_LOCALE;
+#[cfg(feature = "mongodb")]
+use crate::store::value_key;
+use crate::traits::{Context, ValidationResult, Validator};
 
 /// Factory for creating validation rules
 pub struct Rule;
@@ -70,19 +100,7 @@ impl Rule {
 
     /// Validates that value is a string (or null)
     pub fn string() -> impl Validator {
-        move |value: &Value| {
-            if value.is_null() {
-                return Ok(())
-            }
-            if !value.is_string() {
-                Err(ValidationError::TypeError {
-                    expected: "string".to_string(),
-                    got: value.to_string()
-                })
-            } else {
-                Ok(())
-            }
-        }
+        TypeValidator { kind: ValueType::String }
     }
 
     /// Validates that value is an array (or null)
@@ -92,24 +110,13 @@ impl Rule {
     /// ```
     /// use serde_json::json;
     /// use validate_ro::rules::Rule;
+    /// use validate_ro::traits::Validator;
     ///
     /// let validator = Rule::array();
     /// assert!(validator.validate(&json!([1, 2, 3])).is_ok());
     /// ```
     pub fn array() -> impl Validator {
-        move |value: &Value| {
-            if value.is_null() {
-                return Ok(())
-            }
-            if !value.is_array() {
-                Err(ValidationError::TypeError {
-                    expected: "array".to_string(),
-                    got: value.to_string()
-                })
-            } else {
-                Ok(())
-            }
-        }
+        TypeValidator { kind: ValueType::Array }
     }
 
     /// Validates that value is an object (or null)
@@ -119,24 +126,13 @@ impl Rule {
     /// ```
     /// use serde_json::json;
     /// use validate_ro::rules::Rule;
+    /// use validate_ro::traits::Validator;
     ///
     /// let validator = Rule::object();
     /// assert!(validator.validate(&json!({"key": "value"})).is_ok());
     /// ```
     pub fn object() -> impl Validator {
-        move |value: &Value| {
-            if value.is_null() {
-                return Ok(())
-            }
-            if !value.is_object() {
-                Err(ValidationError::TypeError {
-                    expected: "object".to_string(),
-                    got: value.to_string()
-                })
-            } else {
-                Ok(())
-            }
-        }
+        TypeValidator { kind: ValueType::Object }
     }
 
     /// Validates that value is a boolean (or null)
@@ -146,24 +142,13 @@ impl Rule {
     /// ```
     /// use serde_json::json;
     /// use validate_ro::rules::Rule;
+    /// use validate_ro::traits::Validator;
     ///
     /// let validator = Rule::boolean();
     /// assert!(validator.validate(&json!(true)).is_ok());
     /// ```
     pub fn boolean() -> impl Validator {
-        move |value: &Value| {
-            if value.is_null() {
-                return Ok(())
-            }
-            if !value.is_boolean() {
-                Err(ValidationError::TypeError {
-                    expected: "bool".to_string(),
-                    got: value.to_string()
-                })
-            } else {
-                Ok(())
-            }
-        }
+        TypeValidator { kind: ValueType::Boolean }
     }
 
     /// Validates that value is a float number (or null)
@@ -173,24 +158,13 @@ impl Rule {
     /// ```
     /// use serde_json::json;
     /// use validate_ro::rules::Rule;
+    /// use validate_ro::traits::Validator;
     ///
     /// let validator = Rule::float();
     /// assert!(validator.validate(&json!(3.14)).is_ok());
     /// ```
     pub fn float() -> impl Validator {
-        move |value: &Value| {
-            if value.is_null() {
-                return Ok(())
-            }
-            if !value.is_f64() {
-                Err(ValidationError::TypeError {
-                    expected: "float".to_string(),
-                    got: value.to_string()
-                })
-            } else {
-                Ok(())
-            }
-        }
+        TypeValidator { kind: ValueType::Float }
     }
 
     /// Validates that value is an integer (or null)
@@ -200,24 +174,95 @@ impl Rule {
     /// ```
     /// use serde_json::json;
     /// use validate_ro::rules::Rule;
+    /// use validate_ro::traits::Validator;
     ///
     /// let validator = Rule::integer();
     /// assert!(validator.validate(&json!(42)).is_ok());
     /// ```
     pub fn integer() -> impl Validator {
-        move |value: &Value| {
-            if value.is_null() {
-                return Ok(())
-            }
-            if !value.is_i64() {
-                Err(ValidationError::TypeError {
-                    expected: "int".to_string(),
-                    got: value.to_string()
-                })
-            } else {
-                Ok(())
-            }
-        }
+        TypeValidator { kind: ValueType::Integer }
+    }
+
+    /// Validates that value is an integer in the BSON Int32 range (or null)
+    ///
+    /// With bson input the value must also be an Int32, not an Int64.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::rules::Rule;
+    /// use validate_ro::traits::Validator;
+    ///
+    /// let validator = Rule::int32();
+    /// assert!(validator.validate(&json!(42)).is_ok());
+    /// assert!(validator.validate(&json!(5_000_000_000_i64)).is_err());
+    /// ```
+    #[cfg(feature = "mongodb")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mongodb")))]
+    pub fn int32() -> impl Validator {
+        TypeValidator { kind: ValueType::Int32 }
+    }
+
+    /// Validates that value is an integer in the BSON Int64 range (or null)
+    ///
+    /// With bson input the value must also be an Int64, not an Int32. Bson
+    /// input keeps its Int64 values as Int64 in the output.
+    #[cfg(feature = "mongodb")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mongodb")))]
+    pub fn int64() -> impl Validator {
+        TypeValidator { kind: ValueType::Int64 }
+    }
+
+    /// Validates that value is a BSON ObjectId (or null)
+    ///
+    /// ObjectIds are represented in extended JSON, as `{"$oid": "<hex>"}`,
+    /// which is what Bson input is converted to. With coercion, 24 digit hex
+    /// strings are converted to ObjectIds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::rules::Rule;
+    /// use validate_ro::traits::Validator;
+    ///
+    /// let validator = Rule::object_id();
+    /// assert!(validator.validate(&json!({"$oid": "65a1b2c3d4e5f60718293a4b"})).is_ok());
+    /// assert!(validator.validate(&json!("65a1b2c3d4e5f60718293a4b")).is_err());
+    /// ```
+    #[cfg(feature = "mongodb")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mongodb")))]
+    pub fn object_id() -> impl Validator {
+        TypeValidator { kind: ValueType::ObjectId }
+    }
+
+    /// Validates that value is a BSON DateTime (or null)
+    ///
+    /// DateTimes are represented in extended JSON, as `{"$date": ...}`.
+    #[cfg(feature = "mongodb")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mongodb")))]
+    pub fn datetime() -> impl Validator {
+        TypeValidator { kind: ValueType::DateTime }
+    }
+
+    /// Validates that value is a BSON Decimal128 (or null)
+    ///
+    /// Decimals are represented in extended JSON, as `{"$numberDecimal": "<digits>"}`.
+    #[cfg(feature = "mongodb")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mongodb")))]
+    pub fn decimal128() -> impl Validator {
+        TypeValidator { kind: ValueType::Decimal128 }
+    }
+
+    /// Validates that value is BSON binary data (or null)
+    ///
+    /// Binary data is represented in extended JSON, as
+    /// `{"$binary": {"base64": ..., "subType": ...}}`.
+    #[cfg(feature = "mongodb")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mongodb")))]
+    pub fn binary() -> impl Validator {
+        TypeValidator { kind: ValueType::Binary }
     }
 
 
@@ -232,6 +277,7 @@ impl Rule {
     /// ```
     /// use serde_json::json;
     /// use validate_ro::rules::Rule;
+    /// use validate_ro::traits::Validator;
     ///
     /// let validator = Rule::length(3);
     /// assert!(validator.validate(&json!("abc")).is_ok());
@@ -253,6 +299,7 @@ impl Rule {
     /// ```
     /// use serde_json::json;
     /// use validate_ro::rules::Rule;
+    /// use validate_ro::traits::Validator;
     ///
     /// let validator = Rule::min_length(5);
     /// assert!(validator.validate(&json!("long enough")).is_ok());
@@ -273,6 +320,7 @@ impl Rule {
     /// ```
     /// use serde_json::json;
     /// use validate_ro::rules::Rule;
+    /// use validate_ro::traits::Validator;
     ///
     /// let validator = Rule::max_length(10);
     /// assert!(validator.validate(&json!("short")).is_ok());
@@ -293,6 +341,7 @@ impl Rule {
     /// ```
     /// use serde_json::json;
     /// use validate_ro::rules::Rule;
+    /// use validate_ro::traits::Validator;
     ///
     /// let validator = Rule::equal(json!("expected"));
     /// assert!(validator.validate(&json!("expected")).is_ok());
@@ -313,6 +362,7 @@ impl Rule {
     /// ```
     /// use serde_json::json;
     /// use validate_ro::rules::Rule;
+    /// use validate_ro::traits::Validator;
     ///
     /// let validator = Rule::min_value(18.0);
     /// assert!(validator.validate(&json!(21)).is_ok());
@@ -333,6 +383,7 @@ impl Rule {
     /// ```
     /// use serde_json::json;
     /// use validate_ro::rules::Rule;
+    /// use validate_ro::traits::Validator;
     ///
     /// let validator = Rule::max_value(100.0);
     /// assert!(validator.validate(&json!(75)).is_ok());
@@ -349,6 +400,7 @@ impl Rule {
     /// ```
     /// use serde_json::json;
     /// use validate_ro::rules::Rule;
+    /// use validate_ro::traits::Validator;
     ///
     /// let validator = Rule::numeric();
     /// assert!(validator.validate(&json!("123.45")).is_ok());
@@ -358,10 +410,9 @@ impl Rule {
             if value.is_null() {
                 return Ok(())
             }
-            if let Value::String(s) = value {
-                if s.parse::<f64>().is_ok() {
-                    return Ok(());
-                }
+            if let Value::String(s) = value
+                && s.parse::<f64>().is_ok() {
+                return Ok(());
             }
             Err(ValidationError::NumericError(value.to_string()))
         }
@@ -374,6 +425,7 @@ impl Rule {
     /// ```
     /// use serde_json::json;
     /// use validate_ro::rules::Rule;
+    /// use validate_ro::traits::Validator;
     ///
     /// let validator = Rule::accepted();
     /// assert!(validator.validate(&json!("yes")).is_ok());
@@ -416,7 +468,6 @@ impl Rule {
     /// // Only allow @company.com emails
     /// let validator = Rule::email(Some(vec!["company.com".to_string()]));
     /// ```
-
     pub fn email(allowed_domains: Option<Vec<String>>) -> impl Validator {
         EmailValidator {
             allowed_domains: allowed_domains.map(|v| v.into_iter().collect()),
@@ -434,6 +485,7 @@ impl Rule {
     /// ```
     /// use serde_json::json;
     /// use validate_ro::rules::Rule;
+    /// use validate_ro::traits::Validator;
     ///
     /// let validator = Rule::in_values(vec![json!("red"), json!("blue")]);
     /// assert!(validator.validate(&json!("red")).is_ok());
@@ -456,6 +508,7 @@ impl Rule {
     /// ```
     /// use serde_json::json;
     /// use validate_ro::rules::Rule;
+    /// use validate_ro::traits::Validator;
     ///
     /// let validator = Rule::not_in_values(vec![json!("admin")]);
     /// assert!(validator.validate(&json!("user")).is_ok());
@@ -478,6 +531,7 @@ impl Rule {
     /// ```
     /// use serde_json::json;
     /// use validate_ro::rules::Rule;
+    /// use validate_ro::traits::Validator;
     ///
     /// let validator = Rule::regex(r"^\d{3}-\d{3}$", None).unwrap();
     /// assert!(validator.validate(&json!("123-456")).is_ok());
@@ -496,6 +550,7 @@ impl Rule {
     /// ```
     /// use serde_json::json;
     /// use validate_ro::rules::Rule;
+    /// use validate_ro::traits::Validator;
     ///
     /// let validator = Rule::url();
     /// assert!(validator.validate(&json!("https://example.com")).is_ok());
@@ -530,6 +585,7 @@ impl Rule {
     /// ```
     /// use serde_json::json;
     /// use validate_ro::rules::Rule;
+    /// use validate_ro::traits::Validator;
     ///
     /// let validator = Rule::ip();
     /// assert!(validator.validate(&json!("192.168.1.1")).is_ok());
@@ -548,10 +604,9 @@ impl Rule {
             };
 
             let re = Regex::new(r"^(\d{1,3})\.(\d{1,3})\.(\d{1,3})\.(\d{1,3})$").unwrap();
-            if let Some(caps) = re.captures(s) {
-                if caps.iter().skip(1).all(|m| m.unwrap().as_str().parse::<u8>().is_ok()) {
-                    return Ok(());
-                }
+            if let Some(caps) = re.captures(s)
+                && caps.iter().skip(1).all(|m| m.unwrap().as_str().parse::<u8>().is_ok()) {
+                return Ok(());
             }
             Err(ValidationError::IpError(s.clone()))
         }
@@ -568,6 +623,7 @@ impl Rule {
     /// ```
     /// use serde_json::json;
     /// use validate_ro::rules::Rule;
+    /// use validate_ro::traits::Validator;
     ///
     /// let validator = Rule::extensions(vec!["png".into(), "jpg".into()]);
     /// assert!(validator.validate(&json!("image.png")).is_ok());
@@ -578,6 +634,265 @@ impl Rule {
         }
     }
 
+    /// Validates that value is not null when another field equals `value`
+    ///
+    /// Conditional presence rules need the form data, so they only work when
+    /// run by `FormValidator`. A field is present when it is not null.
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - Path of the field to check
+    /// * `value` - Value that makes this field required
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::FormValidator;
+    /// use validate_ro::rules::Rule;
+    ///
+    /// let validator = FormValidator::new()
+    ///     .add("company", Rule::required_if("account_type", json!("business")));
+    /// assert!(validator.validate_json(&json!({"account_type": "business"})).is_err());
+    /// assert!(validator.validate_json(&json!({"account_type": "personal"})).is_ok());
+    /// ```
+    pub fn required_if(field: &str, value: Value) -> impl Validator {
+        PresenceValidator {
+            condition: Presence::RequiredIf(field.to_string(), value),
+        }
+    }
+
+    /// Validates that value is not null unless another field equals `value`
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - Path of the field to check
+    /// * `value` - Value that makes this field optional
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::FormValidator;
+    /// use validate_ro::rules::Rule;
+    ///
+    /// let validator = FormValidator::new()
+    ///     .add("address", Rule::required_unless("delivery", json!("pickup")));
+    /// assert!(validator.validate_json(&json!({"delivery": "pickup"})).is_ok());
+    /// assert!(validator.validate_json(&json!({"delivery": "courier"})).is_err());
+    /// ```
+    pub fn required_unless(field: &str, value: Value) -> impl Validator {
+        PresenceValidator {
+            condition: Presence::RequiredUnless(field.to_string(), value),
+        }
+    }
+
+    /// Validates that value is not null when any of the fields is present
+    ///
+    /// # Arguments
+    ///
+    /// * `fields` - Paths of the fields to check
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::FormValidator;
+    /// use validate_ro::rules::Rule;
+    ///
+    /// let validator = FormValidator::new()
+    ///     .add("city", Rule::required_with(&["street", "zip"]));
+    /// assert!(validator.validate_json(&json!({"zip": "12345"})).is_err());
+    /// ```
+    pub fn required_with(fields: &[&str]) -> impl Validator {
+        PresenceValidator {
+            condition: Presence::RequiredWith(to_strings(fields)),
+        }
+    }
+
+    /// Validates that value is not null when all of the fields are present
+    ///
+    /// # Arguments
+    ///
+    /// * `fields` - Paths of the fields to check
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::FormValidator;
+    /// use validate_ro::rules::Rule;
+    ///
+    /// let validator = FormValidator::new()
+    ///     .add("cvv", Rule::required_with_all(&["card_number", "expiry"]));
+    /// assert!(validator.validate_json(&json!({"card_number": "4111"})).is_ok());
+    /// ```
+    pub fn required_with_all(fields: &[&str]) -> impl Validator {
+        PresenceValidator {
+            condition: Presence::RequiredWithAll(to_strings(fields)),
+        }
+    }
+
+    /// Validates that value is not null when any of the fields is missing
+    ///
+    /// # Arguments
+    ///
+    /// * `fields` - Paths of the fields to check
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::FormValidator;
+    /// use validate_ro::rules::Rule;
+    ///
+    /// let validator = FormValidator::new()
+    ///     .add("email", Rule::required_without(&["phone"]));
+    /// assert!(validator.validate_json(&json!({})).is_err());
+    /// assert!(validator.validate_json(&json!({"phone": "555-0100"})).is_ok());
+    /// ```
+    pub fn required_without(fields: &[&str]) -> impl Validator {
+        PresenceValidator {
+            condition: Presence::RequiredWithout(to_strings(fields)),
+        }
+    }
+
+    /// Validates that value is null when another field equals `value`
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - Path of the field to check
+    /// * `value` - Value that forbids this field
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::FormValidator;
+    /// use validate_ro::rules::Rule;
+    ///
+    /// let validator = FormValidator::new()
+    ///     .add("coupon", Rule::prohibited_if("plan", json!("free")));
+    /// assert!(validator.validate_json(&json!({"plan": "free", "coupon": "X"})).is_err());
+    /// ```
+    pub fn prohibited_if(field: &str, value: Value) -> impl Validator {
+        PresenceValidator {
+            condition: Presence::ProhibitedIf(field.to_string(), value),
+        }
+    }
+
+    /// Validates that value equals another field of the form
+    ///
+    /// Cross-field rules need the form data, so they only work when run by
+    /// `FormValidator`. Field paths may use `*` to refer to the same array
+    /// element as the validated field.
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - Path of the field to compare with
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::FormValidator;
+    /// use validate_ro::rules::Rule;
+    ///
+    /// let validator = FormValidator::new()
+    ///     .add("repeat_email", Rule::same("email"));
+    /// assert!(validator.validate_json(&json!({"email": "a@b.com", "repeat_email": "a@b.com"})).is_ok());
+    /// ```
+    pub fn same(field: &str) -> impl Validator {
+        FieldComparisonValidator::new(Comparison::Same, Some(field))
+    }
+
+    /// Validates that value differs from another field of the form
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - Path of the field to compare with
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::FormValidator;
+    /// use validate_ro::rules::Rule;
+    ///
+    /// let validator = FormValidator::new()
+    ///     .add("new_password", Rule::different("old_password"));
+    /// assert!(validator.validate_json(&json!({"old_password": "a", "new_password": "a"})).is_err());
+    /// ```
+    pub fn different(field: &str) -> impl Validator {
+        FieldComparisonValidator::new(Comparison::Different, Some(field))
+    }
+
+    /// Validates that value equals the `<field>_confirmation` field next to it
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::FormValidator;
+    /// use validate_ro::rules::Rule;
+    ///
+    /// let validator = FormValidator::new()
+    ///     .add("password", Rule::confirmed());
+    /// let data = json!({"password": "secret", "password_confirmation": "secret"});
+    /// assert!(validator.validate_json(&data).is_ok());
+    /// ```
+    pub fn confirmed() -> impl Validator {
+        FieldComparisonValidator::new(Comparison::Confirmed, None)
+    }
+
+    /// Validates that value is greater than another field of the form
+    ///
+    /// Numbers are compared numerically and strings lexicographically, which
+    /// orders ISO 8601 dates correctly. Passes when the other field is null.
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - Path of the field to compare with
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::FormValidator;
+    /// use validate_ro::rules::Rule;
+    ///
+    /// let validator = FormValidator::new()
+    ///     .add("end_date", Rule::gt_field("start_date"));
+    /// let data = json!({"start_date": "2024-01-01", "end_date": "2024-02-01"});
+    /// assert!(validator.validate_json(&data).is_ok());
+    /// ```
+    pub fn gt_field(field: &str) -> impl Validator {
+        FieldComparisonValidator::new(Comparison::Gt, Some(field))
+    }
+
+    /// Validates that value is less than another field of the form
+    ///
+    /// Compares the same way as `gt_field`.
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - Path of the field to compare with
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::FormValidator;
+    /// use validate_ro::rules::Rule;
+    ///
+    /// let validator = FormValidator::new()
+    ///     .add("min_price", Rule::lt_field("max_price"));
+    /// assert!(validator.validate_json(&json!({"min_price": 10, "max_price": 5})).is_err());
+    /// ```
+    pub fn lt_field(field: &str) -> impl Validator {
+        FieldComparisonValidator::new(Comparison::Lt, Some(field))
+    }
+
     /// Creates custom validator from closure
     ///
     /// # Arguments
@@ -606,46 +921,207 @@ impl Rule {
         validator
     }
 
-    /// Validates field value is unique in MongoDB collection
+    /// Validates field value is unique in a collection of the data store
+    ///
+    /// Checked by `validate_async` against the `Store` passed to it, e.g. a
+    /// `mongodb::Database`. Values can be strings, numbers or ObjectIds,
+    /// DateTimes, Decimal128 and Int64 values in extended JSON.
     ///
     /// # Arguments
     ///
-    /// * `collection` - MongoDB collection name
+    /// * `collection` - Collection name
     /// * `field` - Field name to check uniqueness
     /// * `exclude` - Optional document ID to exclude from check (for updates)
     ///
     /// # Example
     ///
     /// ```rust
+    /// use mongodb::bson::oid::ObjectId;
     /// use validate_ro::rules::Rule;
     ///
+    /// # let user_id = ObjectId::new();
     /// // For new documents:
     /// let validator = Rule::unique("users", "email", None);
     ///
     /// // When updating document:
     /// let validator = Rule::unique("users", "email", Some(user_id));
+    ///
+    /// // Slug unique per tenant among non-deleted posts, ignoring case:
+    /// use serde_json::json;
+    /// use validate_ro::store::Filter;
+    ///
+    /// let validator = Rule::unique("posts", "slug", None)
+    ///     .scope("tenant_id")
+    ///     .filter(Filter::Ne("deleted".to_string(), json!(true)))
+    ///     .case_insensitive();
     /// ```
-    pub fn unique(collection: &str, field: &str,exclude:Option<ObjectId>) -> impl Validator {
+    #[cfg(feature = "mongodb")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mongodb")))]
+    pub fn unique(collection: &str, field: &str,exclude:Option<ObjectId>) -> UniqueValidator {
         UniqueValidator::new(collection, field,exclude)
     }
+
+    /// Validates field value references an existing document in a collection
+    ///
+    /// Checked by `validate_async` against the `Store` passed to it. Values
+    /// can be strings, numbers or extended JSON values like `{"$oid": ..}`;
+    /// for arrays every element must reference an existing document.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - Collection name
+    /// * `field` - Field of the referenced documents holding the value
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde_json::json;
+    /// use validate_ro::rules::Rule;
+    /// use validate_ro::store::Filter;
+    ///
+    /// let validator = Rule::exists("categories", "_id")
+    ///     .object_id()
+    ///     .filter(Filter::Ne("deleted".to_string(), json!(true)));
+    /// ```
+    pub fn exists(collection: &str, field: &str) -> ExistsValidator {
+        ExistsValidator::new(collection, field)
+    }
 }
 
-struct UniqueValidator {
+/// Uniqueness check created by `Rule::unique`
+#[cfg(feature = "mongodb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "mongodb")))]
+pub struct UniqueValidator {
     collection: String,
     field: String,
-    current_id: Option<ObjectId>,
+    exclude: Option<(String, Value)>,
+    filters: Vec<Filter>,
+    scope: Vec<String>,
+    /// Locale of the collation used to compare strings ignoring case
+    case_insensitive: Option<String>,
 }
 
+#[cfg(feature = "mongodb")]
 impl UniqueValidator {
     pub fn new(collection: &str, field: &str,exclude:Option<ObjectId>) -> Self {
         Self {
             collection: collection.to_string(),
             field: field.to_string(),
-            current_id: exclude,
+            exclude: exclude.map(|id| ("_id".to_string(), serde_json::json!({"$oid": id.to_hex()}))),
+            filters: Vec::new(),
+            scope: Vec::new(),
+            case_insensitive: None,
+        }
+    }
+
+    /// Ignores the document whose `field` equals `value` (e.g. the one being updated)
+    ///
+    /// Replaces the `_id` given to `Rule::unique`.
+    pub fn exclude(mut self, field: &str, value: Value) -> Self {
+        self.exclude = Some((field.to_string(), value));
+        self
+    }
+
+    /// Adds a condition the conflicting documents must also satisfy
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Only conflicts with documents sharing the form's value of `field`
+    ///
+    /// The form field and the document field have the same name. Requires
+    /// the form context, so it only applies through `FormValidator`. The form
+    /// value must be a string, number, boolean or null; objects and arrays
+    /// fail with a `TypeError`.
+    pub fn scope(mut self, field: &str) -> Self {
+        self.scope.push(field.to_string());
+        self
+    }
+
+    /// Compares string values ignoring case
+    ///
+    /// Stores that support collations (e.g. `mongodb::Database`) run the
+    /// check with a case-insensitive collation for the "en" locale, so that a
+    /// unique index with the same collation can answer it. The collation
+    /// applies to the whole query, including string scope values and filters.
+    pub fn case_insensitive(self) -> Self {
+        self.collation(DEFAULT_COLLATION_LOCALE)
+    }
+
+    /// Compares string values ignoring case, using a collation for `locale`
+    ///
+    /// Like `case_insensitive`, for indexes created with another locale.
+    pub fn collation(mut self, locale: &str) -> Self {
+        self.case_insensitive = Some(locale.to_string());
+        self
+    }
+
+    /// Key shared by values that conflict with each other within one batch
+    pub(crate) fn batch_key(&self, value: &Value, ctx: &Context<'_>) -> Option<String> {
+        let value = match value {
+            Value::String(s) if self.case_insensitive.is_some() => Value::String(s.to_lowercase()),
+            Value::String(_) | Value::Number(_) => value.clone(),
+            _ if is_extended_json_scalar(value) => value.clone(),
+            _ => return None,
+        };
+        let scope: Vec<String> = self.scope.iter().map(|field| value_key(ctx.get(field))).collect();
+        Some(format!("{}\u{0}{}\u{0}{:?}\u{0}{}", self.collection, self.field, scope, value_key(&value)))
+    }
+
+    async fn check(&self, store: &dyn Store, value: &Value, ctx: Option<&Context<'_>>) -> ValidationResult {
+        if value.is_null() {
+            return Ok(());
+        }
+
+        if !(value.is_string() || value.is_i64() || value.is_f64() || is_extended_json_scalar(value)) {
+            return Err(ValidationError::TypeError {
+                expected: "string or number".to_string(),
+                got: value.to_string(),
+            });
+        }
+
+        let mut query = match (value, &self.case_insensitive) {
+            (Value::String(s), Some(locale)) => {
+                Query::new(&self.collection).eq_ignore_case(&self.field, s).collation(locale)
+            }
+            _ => Query::new(&self.collection).eq(&self.field, value.clone()),
+        };
+
+        if let Some((field, value)) = &self.exclude {
+            query = query.ne(field, value.clone());
+        }
+
+        if !self.scope.is_empty() {
+            let Some(ctx) = ctx else {
+                return Err(ValidationError::Custom("Form context required".to_string()));
+            };
+            for field in &self.scope {
+                // Objects would reach the store as query operators (e.g. {"$ne": ..})
+                let scope_value = ctx.get(field);
+                if scope_value.is_object() || scope_value.is_array() {
+                    return Err(ValidationError::TypeError {
+                        expected: "string, number, boolean or null".to_string(),
+                        got: scope_value.to_string(),
+                    });
+                }
+                query = query.eq(field, scope_value.clone());
+            }
+        }
+
+        query.filters.extend(self.filters.iter().cloned());
+
+        match store.count(&query).await {
+            Ok(count) if count > 0 => {
+                Err(ValidationError::UniqueError)
+            }
+            Ok(_) => Ok(()),
+            Err(e) => Err(ValidationError::Store(e)),
         }
     }
 }
 
+#[cfg(feature = "mongodb")]
 #[async_trait]
 impl Validator for UniqueValidator {
     fn validate(&self, value: &Value) -> ValidationResult {
@@ -656,43 +1132,423 @@ impl Validator for UniqueValidator {
         Err(ValidationError::Custom("Async validation required".to_string()))
     }
 
-    async fn validate_async(&self, db: &Database, value: &Value) -> ValidationResult {
-        if value.is_null() {
-            return Ok(());
+    async fn validate_async(&self, store: &dyn Store, value: &Value) -> ValidationResult {
+        self.check(store, value, None).await
+    }
+
+    async fn validate_async_with_context(&self, store: &dyn Store, value: &Value, ctx: &Context<'_>) -> ValidationResult {
+        self.check(store, value, Some(ctx)).await
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Reference check created by `Rule::exists`
+pub struct ExistsValidator {
+    collection: String,
+    field: String,
+    filters: Vec<Filter>,
+    object_id: bool,
+}
+
+impl ExistsValidator {
+    pub fn new(collection: &str, field: &str) -> Self {
+        Self {
+            collection: collection.to_string(),
+            field: field.to_string(),
+            filters: Vec::new(),
+            object_id: false,
         }
+    }
 
-        let collection: Collection<Document> = db.collection(&self.collection);
-        let field_value = match value {
-            Value::String(s) => Bson::String(s.clone()),
-            Value::Number(n) if n.is_i64() => Bson::Int64(n.as_i64().unwrap()),
-            Value::Number(n) if n.is_f64() => Bson::Double(n.as_f64().unwrap()),
-            _ => return Err(ValidationError::TypeError {
+    /// Adds a condition the referenced document must also satisfy
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Treats string values as hex ObjectIds
+    ///
+    /// Strings that are not valid ObjectIds never match.
+    pub fn object_id(mut self) -> Self {
+        self.object_id = true;
+        self
+    }
+
+    fn lookup_value(&self, value: &Value) -> Result<Option<Value>, ValidationError> {
+        match value {
+            Value::String(s) if self.object_id => {
+                Ok(is_object_id_hex(s).then(|| serde_json::json!({"$oid": s.to_lowercase()})))
+            }
+            Value::String(_) | Value::Number(_) => Ok(Some(value.clone())),
+            _ if is_extended_json_scalar(value) => Ok(Some(value.clone())),
+            _ => Err(ValidationError::TypeError {
                 expected: "string or number".to_string(),
                 got: value.to_string(),
             }),
+        }
+    }
+}
+
+#[async_trait]
+impl Validator for ExistsValidator {
+    fn validate(&self, value: &Value) -> ValidationResult {
+        // This is a placeholder - actual async validation needs to happen in validate_async
+        if value.is_null() {
+            return Ok(());
+        }
+        Err(ValidationError::Custom("Async validation required".to_string()))
+    }
+
+    async fn validate_async(&self, store: &dyn Store, value: &Value) -> ValidationResult {
+        let values = match value {
+            Value::Null => return Ok(()),
+            Value::Array(items) => items.iter().collect(),
+            _ => vec![value],
         };
 
-        let mut filter = doc! { &self.field: field_value };
+        for value in values {
+            let Some(lookup) = self.lookup_value(value)? else {
+                return Err(ValidationError::ExistsError);
+            };
+
+            let mut query = Query::new(&self.collection).eq(&self.field, lookup);
+            query.filters.extend(self.filters.iter().cloned());
 
-        if let Some(current_id) = &self.current_id {
-                filter.insert("_id", doc! { "$ne": current_id });
+            match store.count(&query).await {
+                Ok(0) => return Err(ValidationError::ExistsError),
+                Ok(_) => {}
+                Err(e) => return Err(ValidationError::Store(e)),
+            }
         }
 
-        match collection.count_documents(filter).await {
-            Ok(count) if count > 0 => {
-                Err(ValidationError::UniqueError)
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+fn to_strings(fields: &[&str]) -> Vec<String> {
+    fields.iter().map(|f| f.to_string()).collect()
+}
+
+enum Presence {
+    RequiredIf(String, Value),
+    RequiredUnless(String, Value),
+    RequiredWith(Vec<String>),
+    RequiredWithAll(Vec<String>),
+    RequiredWithout(Vec<String>),
+    ProhibitedIf(String, Value),
+}
+
+struct PresenceValidator {
+    condition: Presence,
+}
+
+#[async_trait]
+impl Validator for PresenceValidator {
+    fn validate(&self, value: &Value) -> ValidationResult {
+        // Presence of other fields needs the form, see validate_with_context;
+        // without it only values the rule could never reject pass
+        let prohibition = matches!(self.condition, Presence::ProhibitedIf(..));
+        if value.is_null() == prohibition {
+            return Ok(());
+        }
+        Err(ValidationError::Custom("Form context required".to_string()))
+    }
+
+    fn validate_with_context(&self, value: &Value, ctx: &Context<'_>) -> ValidationResult {
+        let present = |field: &String| !ctx.get(field).is_null();
+
+        match &self.condition {
+            Presence::RequiredIf(field, expected) if value.is_null() && ctx.get(field) == expected => {
+                Err(ValidationError::RequiredIfError {
+                    field: field.clone(),
+                    value: expected.to_string(),
+                })
             }
-            Ok(_) => Ok(()),
-            Err(_) => {
-                Err(ValidationError::Custom("Database error".to_string()))
+            Presence::RequiredUnless(field, expected) if value.is_null() && ctx.get(field) != expected => {
+                Err(ValidationError::RequiredUnlessError {
+                    field: field.clone(),
+                    value: expected.to_string(),
+                })
+            }
+            Presence::RequiredWith(fields) if value.is_null() && fields.iter().any(present) => {
+                Err(ValidationError::RequiredWithError(fields.clone()))
+            }
+            Presence::RequiredWithAll(fields) if value.is_null() && fields.iter().all(present) => {
+                Err(ValidationError::RequiredWithAllError(fields.clone()))
+            }
+            Presence::RequiredWithout(fields) if value.is_null() && !fields.iter().all(present) => {
+                Err(ValidationError::RequiredWithoutError(fields.clone()))
             }
+            Presence::ProhibitedIf(field, expected) if !value.is_null() && ctx.get(field) == expected => {
+                Err(ValidationError::ProhibitedIfError {
+                    field: field.clone(),
+                    value: expected.to_string(),
+                })
+            }
+            _ => Ok(()),
         }
     }
 
+    async fn validate_async_with_context(&self, _store: &dyn Store, value: &Value, ctx: &Context<'_>) -> ValidationResult {
+        self.validate_with_context(value, ctx)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
 }
+
+enum Comparison {
+    Same,
+    Different,
+    Confirmed,
+    Gt,
+    Lt,
+}
+
+struct FieldComparisonValidator {
+    comparison: Comparison,
+    field: Option<String>,
+}
+
+impl FieldComparisonValidator {
+    fn new(comparison: Comparison, field: Option<&str>) -> Self {
+        Self {
+            comparison,
+            field: field.map(|f| f.to_string()),
+        }
+    }
+
+    fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+        match (a, b) {
+            (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+            (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
+    }
+}
+
+#[async_trait]
+impl Validator for FieldComparisonValidator {
+    fn validate(&self, value: &Value) -> ValidationResult {
+        // Comparing with other fields needs the form, see validate_with_context
+        if value.is_null() {
+            return Ok(());
+        }
+        Err(ValidationError::Custom("Form context required".to_string()))
+    }
+
+    fn validate_with_context(&self, value: &Value, ctx: &Context<'_>) -> ValidationResult {
+        if value.is_null() {
+            return Ok(());
+        }
+        let field = match &self.field {
+            Some(field) => field.clone(),
+            None => format!("{}_confirmation", ctx.path()),
+        };
+        let other = ctx.get(&field);
+
+        match self.comparison {
+            Comparison::Same if value != other => Err(ValidationError::SameError(field)),
+            Comparison::Different if value == other => Err(ValidationError::DifferentError(field)),
+            Comparison::Confirmed if value != other => Err(ValidationError::ConfirmedError),
+            Comparison::Gt | Comparison::Lt if !other.is_null() => {
+                let (expected, error) = match self.comparison {
+                    Comparison::Gt => (Ordering::Greater, ValidationError::GtFieldError(field)),
+                    _ => (Ordering::Less, ValidationError::LtFieldError(field)),
+                };
+                match Self::compare(value, other) {
+                    Some(ordering) if ordering == expected => Ok(()),
+                    Some(_) => Err(error),
+                    None => Err(ValidationError::TypeError {
+                        expected: "number or string".to_string(),
+                        got: value.to_string(),
+                    }),
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    async fn validate_async_with_context(&self, _store: &dyn Store, value: &Value, ctx: &Context<'_>) -> ValidationResult {
+        self.validate_with_context(value, ctx)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ValueType {
+    String,
+    Array,
+    Object,
+    Boolean,
+    Float,
+    Integer,
+    #[cfg(feature = "mongodb")]
+    Int32,
+    #[cfg(feature = "mongodb")]
+    Int64,
+    #[cfg(feature = "mongodb")]
+    ObjectId,
+    #[cfg(feature = "mongodb")]
+    DateTime,
+    #[cfg(feature = "mongodb")]
+    Decimal128,
+    #[cfg(feature = "mongodb")]
+    Binary,
+}
+
+/// Type check created by the basic type rules, also used for coercion
+pub(crate) struct TypeValidator {
+    kind: ValueType,
+}
+
+impl TypeValidator {
+    /// Converts a string into the declared type when it represents one
+    ///
+    /// Empty and "null" strings become null for integer, float and boolean
+    /// types. Strings that can't be converted are returned unchanged so that
+    /// validation reports them.
+    pub(crate) fn coerce(&self, value: Value) -> Value {
+        let s = match &value {
+            Value::String(s) => s.trim(),
+            _ => return value,
+        };
+        let coerced = match self.kind {
+            ValueType::Integer | ValueType::Float | ValueType::Boolean if s.is_empty() || s == "null" => {
+                Some(Value::Null)
+            }
+            ValueType::Integer => s.parse::<i64>().ok().map(Value::from),
+            #[cfg(feature = "mongodb")]
+            ValueType::Int32 if s.is_empty() || s == "null" => Some(Value::Null),
+            #[cfg(feature = "mongodb")]
+            ValueType::Int32 => s.parse::<i32>().ok().map(Value::from),
+            #[cfg(feature = "mongodb")]
+            ValueType::Int64 if s.is_empty() || s == "null" => Some(Value::Null),
+            #[cfg(feature = "mongodb")]
+            ValueType::Int64 => s.parse::<i64>().ok().map(Value::from),
+            #[cfg(feature = "mongodb")]
+            ValueType::ObjectId if is_object_id_hex(s) => Some(serde_json::json!({"$oid": s.to_lowercase()})),
+            ValueType::Float => s.parse::<f64>().ok().and_then(Number::from_f64).map(Value::Number),
+            ValueType::Boolean => match s.to_lowercase().as_str() {
+                "true" | "1" | "on" | "yes" => Some(Value::Bool(true)),
+                "false" | "0" | "off" | "no" => Some(Value::Bool(false)),
+                _ => None,
+            },
+            _ => None,
+        };
+        coerced.unwrap_or(value)
+    }
+
+    /// Rejects Int64 values for `int32` and Int32 values for `int64`
+    ///
+    /// `value` is the validated value and `original` the bson it was read
+    /// from, in which the integer width is still known. Values replaced by a
+    /// default or a transformer are not checked.
+    #[cfg(feature = "mongodb")]
+    pub(crate) fn check_width(&self, value: &Value, original: &Bson) -> ValidationResult {
+        let expected = match (self.kind, original) {
+            (ValueType::Int32, Bson::Int64(n)) if value.as_i64() == Some(*n) => "int32",
+            (ValueType::Int64, Bson::Int32(n)) if value.as_i64() == Some(i64::from(*n)) => "int64",
+            _ => return Ok(()),
+        };
+        Err(ValidationError::TypeError {
+            expected: expected.to_string(),
+            got: original.to_string(),
+        })
+    }
+}
+
+impl Validator for TypeValidator {
+    fn validate(&self, value: &Value) -> ValidationResult {
+        if value.is_null() {
+            return Ok(())
+        }
+        let (valid, expected) = match self.kind {
+            ValueType::String => (value.is_string(), "string"),
+            ValueType::Array => (value.is_array(), "array"),
+            ValueType::Object => (value.is_object(), "object"),
+            ValueType::Boolean => (value.is_boolean(), "bool"),
+            ValueType::Float => (value.is_f64(), "float"),
+            ValueType::Integer => (value.is_i64(), "int"),
+            #[cfg(feature = "mongodb")]
+            ValueType::Int32 => (value.as_i64().is_some_and(|n| i32::try_from(n).is_ok()), "int32"),
+            #[cfg(feature = "mongodb")]
+            ValueType::Int64 => (value.is_i64(), "int64"),
+            #[cfg(feature = "mongodb")]
+            ValueType::ObjectId => (
+                extended_json(value, "$oid").and_then(Value::as_str).is_some_and(is_object_id_hex),
+                "objectId",
+            ),
+            #[cfg(feature = "mongodb")]
+            ValueType::DateTime => (
+                extended_json(value, "$date").is_some_and(|date| date.is_string() || date.get("$numberLong").is_some()),
+                "date",
+            ),
+            #[cfg(feature = "mongodb")]
+            ValueType::Decimal128 => (extended_json(value, "$numberDecimal").is_some_and(Value::is_string), "decimal"),
+            #[cfg(feature = "mongodb")]
+            ValueType::Binary => (extended_json(value, "$binary").is_some_and(|binary| binary.get("base64").is_some()), "binData"),
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(ValidationError::TypeError {
+                expected: expected.to_string(),
+                got: value.to_string()
+            })
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Content of an extended JSON value such as `{"$oid": ...}` with the given key
+#[cfg(feature = "mongodb")]
+fn extended_json<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    match value {
+        Value::Object(map) if map.len() == 1 => map.get(key),
+        _ => None,
+    }
+}
+
+/// Whether a value is an ObjectId, DateTime, Decimal128 or Int64 in extended
+/// JSON, which database rules look up like strings and numbers
+///
+/// With the `mongodb` feature the content must also parse as that type, so
+/// malformed input such as `{"$oid": "nothex"}` is a type error rather than
+/// a store failure.
+fn is_extended_json_scalar(value: &Value) -> bool {
+    let wrapper = match value {
+        Value::Object(map) if map.len() == 1 => {
+            map.keys().all(|key| matches!(key.as_str(), "$oid" | "$date" | "$numberDecimal" | "$numberLong"))
+        }
+        _ => false,
+    };
+    #[cfg(feature = "mongodb")]
+    let wrapper = wrapper && matches!(
+        Bson::try_from(value.clone()),
+        Ok(Bson::ObjectId(_) | Bson::DateTime(_) | Bson::Decimal128(_) | Bson::Int64(_))
+    );
+    wrapper
+}
+
+fn is_object_id_hex(s: &str) -> bool {
+    s.len() == 24 && s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
 struct ExtensionValidator {
     allowed: HashSet<String>,
 }
@@ -710,10 +1566,9 @@ impl Validator for ExtensionValidator {
             }),
         };
 
-        if let Some(ext) = s.split('.').last() {
-            if self.allowed.contains(ext) {
-                return Ok(());
-            }
+        if let Some(ext) = s.split('.').next_back()
+            && self.allowed.contains(ext) {
+            return Ok(());
         }
         Err(ValidationError::ExtensionError(
             self.allowed.iter().cloned().collect(),
@@ -831,10 +1686,9 @@ impl Validator for EmailValidator {
             return Err(ValidationError::EmailError(email.clone()));
         }
 
-        if let Some(allowed) = &self.allowed_domains {
-            if !allowed.contains(domain) {
-                return Err(ValidationError::EmailDomainError(domain.to_string()));
-            }
+        if let Some(allowed) = &self.allowed_domains
+            && !allowed.contains(domain) {
+            return Err(ValidationError::EmailDomainError(domain.to_string()));
         }
 
         if name.len() < 3 {
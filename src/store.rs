@@ -0,0 +1,600 @@
+//! # Data Stores for Async Validation
+//!
+//! Async validators such as `Rule::unique` read data through the `Store`
+//! trait instead of a specific database driver. Queries are described with
+//! serde_json values; special types like MongoDB's ObjectId use the extended
+//! JSON form (`{"$oid": "..."}`), which each backend converts as needed.
+//!
+//! ## Backends
+//! - `mongodb::Database` - MongoDB collections (requires the `mongodb` feature)
+//! - `MemoryStore` - In-memory collections of JSON documents, for tests
+
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use async_trait::async_trait;
+use indexmap::IndexMap;
+#[cfg(feature = "mongodb")]
+use mongodb::bson::{doc, Bson, Document, Regex};
+#[cfg(feature = "mongodb")]
+use mongodb::action::Action;
+#[cfg(feature = "mongodb")]
+use mongodb::options::{Collation, CollationStrength};
+#[cfg(feature = "mongodb")]
+use mongodb::{Collection, Database};
+use serde_json::{Number, Value};
+
+/// Condition on a field of the stored documents
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    /// Field equals the value
+    Eq(String, Value),
+    /// Field differs from the value
+    Ne(String, Value),
+    /// Field equals one of the values
+    In(String, Vec<Value>),
+    /// Field is a string equal to the value, ignoring case
+    EqIgnoreCase(String, String),
+    /// Field is a string equal to one of the values, ignoring case
+    InIgnoreCase(String, Vec<String>),
+}
+
+/// Query matching documents of a collection that satisfy all of its filters
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+/// use validate_ro::store::Query;
+///
+/// let query = Query::new("users")
+///     .eq("email", json!("user@example.com"))
+///     .ne("_id", json!({"$oid": "65a1b2c3d4e5f60718293a4b"}));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub collection: String,
+    pub filters: Vec<Filter>,
+    /// Locale of a case-insensitive collation for the ignore-case filters
+    ///
+    /// Backends that support collations run the whole query with it (e.g.
+    /// MongoDB's strength 2), which lets an index with the same collation
+    /// answer it; the others match the ignore-case filters on their own.
+    pub collation: Option<String>,
+}
+
+impl Query {
+    pub fn new(collection: &str) -> Self {
+        Self {
+            collection: collection.to_string(),
+            filters: Vec::new(),
+            collation: None,
+        }
+    }
+
+    /// Sets the locale of the case-insensitive collation, see `collation`
+    pub fn collation(mut self, locale: &str) -> Self {
+        self.collation = Some(locale.to_string());
+        self
+    }
+
+    /// Adds a filter requiring `field` to equal `value`
+    pub fn eq(mut self, field: &str, value: Value) -> Self {
+        self.filters.push(Filter::Eq(field.to_string(), value));
+        self
+    }
+
+    /// Adds a filter requiring `field` to differ from `value`
+    pub fn ne(mut self, field: &str, value: Value) -> Self {
+        self.filters.push(Filter::Ne(field.to_string(), value));
+        self
+    }
+
+    /// Adds a filter requiring `field` to equal `value`, ignoring case
+    pub fn eq_ignore_case(mut self, field: &str, value: &str) -> Self {
+        self.filters.push(Filter::EqIgnoreCase(field.to_string(), value.to_string()));
+        self
+    }
+
+    /// Adds a filter requiring `field` to equal one of `values`
+    pub fn is_in(mut self, field: &str, values: Vec<Value>) -> Self {
+        self.filters.push(Filter::In(field.to_string(), values));
+        self
+    }
+}
+
+/// Data source used by async validators
+///
+/// Implement it to run async rules against any database or service.
+///
+/// # Example
+///
+/// ```
+/// use async_trait::async_trait;
+/// use validate_ro::store::{Query, Store, StoreError};
+///
+/// struct NoData;
+///
+/// #[async_trait]
+/// impl Store for NoData {
+///     async fn count(&self, _query: &Query) -> Result<u64, StoreError> {
+///         Ok(0)
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait Store: Send + Sync {
+    /// Counts the documents matching the query
+    async fn count(&self, query: &Query) -> Result<u64, StoreError>;
+
+    /// Distinct values of `field` among the documents matching the query
+    ///
+    /// Array fields contribute each of their elements. Used by
+    /// `FormValidator::validate_many_json` to look up many values in one query;
+    /// with stores that do not implement it (see `StoreError::unsupported`),
+    /// `validate_many_json` counts once per record instead.
+    async fn distinct(&self, query: &Query, field: &str) -> Result<Vec<Value>, StoreError> {
+        let _ = (query, field);
+        Err(StoreError::unsupported("distinct"))
+    }
+}
+
+/// Failure of the underlying data store
+///
+/// Keeps the driver error as its `source`.
+#[derive(Debug, Clone)]
+pub struct StoreError(Arc<dyn Error + Send + Sync>);
+
+impl StoreError {
+    pub fn new(error: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        Self(Arc::from(error.into()))
+    }
+
+    /// Error of an operation the store does not implement
+    pub fn unsupported(operation: &str) -> Self {
+        Self::new(Unsupported(operation.to_string()))
+    }
+
+    /// Whether the store does not implement the operation, see `unsupported`
+    pub fn is_unsupported(&self) -> bool {
+        self.0.is::<Unsupported>()
+    }
+}
+
+#[derive(Debug)]
+struct Unsupported(String);
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not supported by this store", self.0)
+    }
+}
+
+impl Error for Unsupported {}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "store error: {}", self.0)
+    }
+}
+
+/// Store errors are equal when their messages are, since driver errors
+/// generally can't be compared
+impl PartialEq for StoreError {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.0.to_string() == other.0.to_string()
+    }
+}
+
+impl Error for StoreError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.0.as_ref())
+    }
+}
+
+/// Store keeping collections of JSON documents in memory
+///
+/// Meant for exercising async rules without a database. Filters follow
+/// MongoDB semantics: dotted paths reach into nested objects, a filter on an
+/// array field matches when any element matches, a missing field equals null
+/// and numbers compare by value (integers exactly).
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+/// use validate_ro::store::{MemoryStore, Query, Store};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let store = MemoryStore::new()
+///     .with_collection("users", vec![
+///         json!({"_id": 1, "email": "taken@example.com"}),
+///     ]);
+///
+/// let query = Query::new("users").eq("email", json!("taken@example.com"));
+/// assert_eq!(store.count(&query).await.unwrap(), 1);
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore {
+    collections: HashMap<String, Vec<Value>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds documents to a collection, creating it if needed
+    pub fn with_collection(mut self, collection: &str, documents: Vec<Value>) -> Self {
+        self.collections
+            .entry(collection.to_string())
+            .or_default()
+            .extend(documents);
+        self
+    }
+
+    /// Adds one document to a collection, creating it if needed
+    pub fn insert(&mut self, collection: &str, document: Value) {
+        self.collections
+            .entry(collection.to_string())
+            .or_default()
+            .push(document);
+    }
+
+    /// Documents of a collection, empty if it does not exist
+    pub fn documents(&self, collection: &str) -> &[Value] {
+        self.collections
+            .get(collection)
+            .map_or(&[], |documents| documents.as_slice())
+    }
+}
+
+#[async_trait]
+impl Store for MemoryStore {
+    async fn count(&self, query: &Query) -> Result<u64, StoreError> {
+        let count = self
+            .documents(&query.collection)
+            .iter()
+            .filter(|document| query.filters.iter().all(|filter| filter_matches(filter, document)))
+            .count();
+        Ok(count as u64)
+    }
+
+    async fn distinct(&self, query: &Query, field: &str) -> Result<Vec<Value>, StoreError> {
+        let mut seen = HashSet::new();
+        let mut values = Vec::new();
+        let matching = self
+            .documents(&query.collection)
+            .iter()
+            .filter(|document| query.filters.iter().all(|filter| filter_matches(filter, document)));
+        for document in matching {
+            let found = match field_value(document, field) {
+                Value::Null => continue,
+                Value::Array(items) => items.clone(),
+                value => vec![value.clone()],
+            };
+            for value in found {
+                if seen.insert(value_key(&value)) {
+                    values.push(value);
+                }
+            }
+        }
+        Ok(values)
+    }
+}
+
+fn filter_matches(filter: &Filter, document: &Value) -> bool {
+    match filter {
+        Filter::Eq(field, value) => field_matches(document, field, value),
+        Filter::Ne(field, value) => !field_matches(document, field, value),
+        Filter::In(field, values) => values.iter().any(|value| field_matches(document, field, value)),
+        Filter::EqIgnoreCase(field, value) => {
+            let expected = Value::String(value.to_lowercase());
+            let lowered = lowercase_strings(document);
+            field_matches(&lowered, field, &expected)
+        }
+        Filter::InIgnoreCase(field, values) => {
+            let lowered = lowercase_strings(document);
+            values.iter().any(|value| field_matches(&lowered, field, &Value::String(value.to_lowercase())))
+        }
+    }
+}
+
+fn lowercase_strings(value: &Value) -> Value {
+    match value {
+        Value::String(s) => Value::String(s.to_lowercase()),
+        Value::Array(items) => Value::Array(items.iter().map(lowercase_strings).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter().map(|(k, v)| (k.clone(), lowercase_strings(v))).collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn field_value<'a>(document: &'a Value, field: &str) -> &'a Value {
+    let mut current = document;
+    for part in field.split('.') {
+        current = match (current, part.parse::<usize>()) {
+            (Value::Array(items), Ok(index)) => items.get(index),
+            _ => current.get(part),
+        }
+        .unwrap_or(&Value::Null);
+    }
+    current
+}
+
+fn field_matches(document: &Value, field: &str, expected: &Value) -> bool {
+    let current = field_value(document, field);
+    match current {
+        Value::Array(items) if !expected.is_array() => items.iter().any(|item| values_equal(item, expected)),
+        _ => values_equal(current, expected),
+    }
+}
+
+/// Integers compare exactly, like MongoDB's Int64; floats by their f64 value
+///
+/// Extended JSON values compare by the value they stand for, so
+/// `{"$numberLong": "5"}` equals 5.
+fn values_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => match (as_integer(a), as_integer(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => a.as_f64() == b.as_f64(),
+        },
+        (Value::Object(_), _) | (_, Value::Object(_)) => match (scalar_key(a), scalar_key(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => a == b,
+        },
+        _ => a == b,
+    }
+}
+
+fn as_integer(n: &Number) -> Option<i128> {
+    n.as_i64().map(i128::from).or_else(|| n.as_u64().map(i128::from))
+}
+
+/// Collation locale of case-insensitive lookups when none is given
+#[cfg(feature = "mongodb")]
+pub(crate) const DEFAULT_COLLATION_LOCALE: &str = "en";
+
+/// Key under which equal values collide
+///
+/// Integers are keyed exactly, and extended JSON values by the value they
+/// stand for, so that input matches what `distinct` returns for it.
+pub(crate) fn value_key(value: &Value) -> String {
+    scalar_key(value).unwrap_or_else(|| value.to_string())
+}
+
+/// Canonical key of a number or an extended JSON scalar
+fn scalar_key(value: &Value) -> Option<String> {
+    match value {
+        Value::Number(n) => Some(match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => i.to_string(),
+            (_, Some(u)) => u.to_string(),
+            _ => float_key(n.as_f64().unwrap_or(f64::NAN)),
+        }),
+        #[cfg(feature = "mongodb")]
+        Value::Object(_) => match Bson::try_from(value.clone()).ok()? {
+            Bson::Int64(n) => Some(n.to_string()),
+            Bson::ObjectId(id) => Some(format!("$oid:{}", id.to_hex())),
+            Bson::DateTime(date) => Some(format!("$date:{}", date.timestamp_millis())),
+            Bson::Decimal128(decimal) => Some(format!("$numberDecimal:{}", decimal)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whole floats share the key of the equal integer
+fn float_key(f: f64) -> String {
+    if f.fract() == 0.0 && f.abs() < i64::MAX as f64 {
+        (f as i64).to_string()
+    } else {
+        f.to_string()
+    }
+}
+
+/// Splits a lookup query into the key shared by its batch, the looked up
+/// field and the value, case-folded when the lookup ignores case
+///
+/// Lookups are queries whose first filter is an equality, with or without
+/// case; the remaining filters must match for two lookups to be answered by
+/// the same query.
+fn lookup_key(query: &Query) -> Option<(String, &str, Value)> {
+    let (first, rest) = query.filters.split_first()?;
+    let (field, value, ignore_case) = match first {
+        Filter::Eq(field, value) => (field, value.clone(), false),
+        Filter::EqIgnoreCase(field, value) => (field, Value::String(value.to_lowercase()), true),
+        _ => return None,
+    };
+    let key = format!("{}\u{0}{}\u{0}{}\u{0}{:?}\u{0}{:?}", query.collection, field, ignore_case, rest, query.collation);
+    Some((key, field, value))
+}
+
+/// Store that records the queries made to it, answering each with one match
+///
+/// A match lets multi-value rules such as `exists` on arrays issue all of
+/// their lookups.
+#[derive(Default)]
+pub(crate) struct RecordingStore {
+    queries: Mutex<Vec<Query>>,
+}
+
+#[async_trait]
+impl Store for RecordingStore {
+    async fn count(&self, query: &Query) -> Result<u64, StoreError> {
+        self.queries.lock().unwrap().push(query.clone());
+        Ok(1)
+    }
+}
+
+/// Answers lookups from values fetched with one `$in` query per batch key,
+/// forwarding any other query to the inner store
+///
+/// Counts of prefetched lookups are 0 or 1.
+pub(crate) struct PrefetchedStore<'a> {
+    inner: &'a dyn Store,
+    found: HashMap<String, HashSet<String>>,
+}
+
+impl<'a> PrefetchedStore<'a> {
+    /// Fetches the values of the recorded lookups
+    ///
+    /// Batches the store can't fetch with `distinct` (see
+    /// `StoreError::is_unsupported`) are left to `count`, one lookup at a time.
+    pub(crate) async fn prefetch(inner: &'a dyn Store, recorder: RecordingStore) -> Result<Self, StoreError> {
+        let mut batches: IndexMap<String, (Query, Vec<Value>)> = IndexMap::new();
+        for query in recorder.queries.into_inner().unwrap() {
+            let Some((key, _, value)) = lookup_key(&query) else {
+                continue;
+            };
+            let (_, values) = batches.entry(key).or_insert_with(|| (query.clone(), Vec::new()));
+            values.push(value);
+        }
+
+        let mut found = HashMap::new();
+        for (key, (mut batch, values)) in batches {
+            let (field, ignore_case) = match &batch.filters[0] {
+                Filter::Eq(field, _) => (field.clone(), false),
+                Filter::EqIgnoreCase(field, _) => (field.clone(), true),
+                _ => unreachable!(),
+            };
+            batch.filters[0] = if ignore_case {
+                let values = values.into_iter().filter_map(|value| value.as_str().map(str::to_string)).collect();
+                Filter::InIgnoreCase(field.clone(), values)
+            } else {
+                Filter::In(field.clone(), values)
+            };
+            let existing = match inner.distinct(&batch, &field).await {
+                Ok(existing) => existing,
+                Err(e) if e.is_unsupported() => continue,
+                Err(e) => return Err(e),
+            };
+            let existing = existing.iter().map(|value| match value {
+                Value::String(s) if ignore_case => value_key(&Value::String(s.to_lowercase())),
+                _ => value_key(value),
+            });
+            found.insert(key, existing.collect());
+        }
+
+        Ok(Self { inner, found })
+    }
+}
+
+#[async_trait]
+impl Store for PrefetchedStore<'_> {
+    async fn count(&self, query: &Query) -> Result<u64, StoreError> {
+        if let Some((key, _, value)) = lookup_key(query)
+            && let Some(found) = self.found.get(&key)
+        {
+            return Ok(found.contains(&value_key(&value)) as u64);
+        }
+        self.inner.count(query).await
+    }
+
+    async fn distinct(&self, query: &Query, field: &str) -> Result<Vec<Value>, StoreError> {
+        self.inner.distinct(query, field).await
+    }
+}
+
+#[cfg(feature = "mongodb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "mongodb")))]
+#[async_trait]
+impl Store for Database {
+    async fn count(&self, query: &Query) -> Result<u64, StoreError> {
+        let collection: Collection<Document> = self.collection(&query.collection);
+        let filter = query_to_document(query)?;
+        collection.count_documents(filter)
+            .optional(to_collation(query), |action, collation| action.collation(collation))
+            .await
+            .map_err(StoreError::new)
+    }
+
+    async fn distinct(&self, query: &Query, field: &str) -> Result<Vec<Value>, StoreError> {
+        let collection: Collection<Document> = self.collection(&query.collection);
+        let filter = query_to_document(query)?;
+        let values = collection.distinct(field, filter)
+            .optional(to_collation(query), |action, collation| action.collation(collation))
+            .await
+            .map_err(StoreError::new)?;
+        Ok(values.into_iter().map(Bson::into_relaxed_extjson).collect())
+    }
+}
+
+/// Case-insensitive collation of a query: strength 2 compares strings
+/// ignoring case but not accents
+#[cfg(feature = "mongodb")]
+fn to_collation(query: &Query) -> Option<Collation> {
+    let locale = query.collation.as_ref()?;
+    Some(Collation::builder().locale(locale).strength(CollationStrength::Secondary).build())
+}
+
+/// Filter document of a query
+///
+/// With a collation the ignore-case filters are plain equalities, left to
+/// the collation; without one they become anchored case-insensitive regexes,
+/// which can't use an index.
+#[cfg(feature = "mongodb")]
+fn query_to_document(query: &Query) -> Result<Document, StoreError> {
+    let to_bson = |value: &Value| Bson::try_from(value.clone()).map_err(StoreError::new);
+
+    let mut conditions = Vec::new();
+    for filter in &query.filters {
+        let mut condition = Document::new();
+        match filter {
+            Filter::Eq(field, value) => {
+                condition.insert(field, to_bson(value)?);
+            }
+            Filter::Ne(field, value) => {
+                condition.insert(field, doc! { "$ne": to_bson(value)? });
+            }
+            Filter::In(field, values) => {
+                let values = values.iter().map(to_bson).collect::<Result<Vec<_>, _>>()?;
+                condition.insert(field, doc! { "$in": values });
+            }
+            Filter::EqIgnoreCase(field, value) if query.collation.is_some() => {
+                condition.insert(field, value);
+            }
+            Filter::InIgnoreCase(field, values) if query.collation.is_some() => {
+                condition.insert(field, doc! { "$in": values });
+            }
+            Filter::EqIgnoreCase(field, value) => {
+                let pattern = format!("^{}$", pcre_escape(value));
+                condition.insert(field, doc! { "$regex": pattern, "$options": "i" });
+            }
+            Filter::InIgnoreCase(field, values) => {
+                let patterns: Vec<Bson> = values
+                    .iter()
+                    .map(|value| Bson::RegularExpression(Regex {
+                        pattern: format!("^{}$", pcre_escape(value)),
+                        options: "i".to_string(),
+                    }))
+                    .collect();
+                condition.insert(field, doc! { "$in": patterns });
+            }
+        }
+        conditions.push(condition);
+    }
+
+    Ok(match conditions.len() {
+        0 => Document::new(),
+        1 => conditions.remove(0),
+        _ => doc! { "$and": conditions },
+    })
+}
+
+/// Escapes every ASCII punctuation character of a string for a MongoDB (PCRE) regex
+#[cfg(feature = "mongodb")]
+fn pcre_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c.is_ascii_punctuation() {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
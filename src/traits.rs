@@ -1,18 +1,31 @@
 use std::any::Any;
 
 use async_trait::async_trait;
-use mongodb::Database;
 use serde_json::Value;
 use crate::error::ValidationError;
+use crate::store::Store;
 
 #[async_trait]
 pub trait Validator: Any+Send + Sync {
     fn validate(&self, value: &Value) -> ValidationResult;
 
-    async fn validate_async(&self, _db: &Database, value: &Value) -> ValidationResult {
+    async fn validate_async(&self, _store: &dyn Store, value: &Value) -> ValidationResult {
         self.validate(value)
     }
 
+    /// Validates a value that is part of a form
+    ///
+    /// Called by `FormValidator`; override it for rules that need to read
+    /// other fields of the form.
+    fn validate_with_context(&self, value: &Value, _ctx: &Context<'_>) -> ValidationResult {
+        self.validate(value)
+    }
+
+    /// Async counterpart of `validate_with_context`
+    async fn validate_async_with_context(&self, store: &dyn Store, value: &Value, _ctx: &Context<'_>) -> ValidationResult {
+        self.validate_async(store, value).await
+    }
+
     fn as_any(&self) -> &dyn Any;
 }
 
@@ -30,4 +43,73 @@ where
     }
 }
 
-pub type ValidationResult = Result<(), ValidationError>;
\ No newline at end of file
+/// Rewrites a value before the following validators of a `Rules` chain run
+pub trait Transformer: Send + Sync {
+    fn transform(&self, value: Value) -> Value;
+}
+
+impl<F> Transformer for F
+where
+    F: Fn(Value) -> Value+Sync+Send+ 'static
+{
+    fn transform(&self, value: Value) -> Value {
+        self(value)
+    }
+}
+
+/// Form data and location of the field being validated
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+/// use validate_ro::traits::Context;
+///
+/// let data = json!({"items": [{"min": 1, "max": 5}]});
+/// let ctx = Context::new(&data, "items.0.max");
+/// assert_eq!(ctx.get("items.*.min"), &json!(1));
+/// ```
+pub struct Context<'a> {
+    root: &'a Value,
+    path: &'a str,
+}
+
+impl<'a> Context<'a> {
+    pub fn new(root: &'a Value, path: &'a str) -> Self {
+        Self { root, path }
+    }
+
+    /// Whole form data
+    pub fn root(&self) -> &'a Value {
+        self.root
+    }
+
+    /// Concrete path of the field being validated (e.g. "items.3.sku")
+    pub fn path(&self) -> &'a str {
+        self.path
+    }
+
+    /// Looks up another field of the form by its dotted path
+    ///
+    /// `*` segments take the index of the field being validated, so
+    /// "items.*.min" refers to the same array element. Missing fields are null.
+    pub fn get(&self, field: &str) -> &'a Value {
+        let mut own_parts = self.path.split('.');
+        let mut current = self.root;
+        for part in field.split('.') {
+            let own_part = own_parts.next();
+            let part = match (part, own_part) {
+                ("*", Some(own)) => own,
+                _ => part,
+            };
+            current = match (current, part.parse::<usize>()) {
+                (Value::Array(items), Ok(index)) => items.get(index),
+                _ => current.get(part),
+            }
+            .unwrap_or(&Value::Null);
+        }
+        current
+    }
+}
+
+pub type ValidationResult = Result<(), ValidationError>;
@@ -0,0 +1,198 @@
+//! # Built-in Value Transformers
+//!
+//! Transformers rewrite a value inside a `Rules` chain before the validators
+//! that follow them run. The transformed value is what `FormValidator`
+//! returns. Values of other types are passed through unchanged.
+//!
+//! ## String Transformers
+//! - `trim()` - Removes leading and trailing whitespace
+//! - `lowercase()` - Converts to lowercase
+//! - `uppercase()` - Converts to uppercase
+//! - `collapse_whitespace()` - Replaces runs of whitespace with a single space
+//! - `strip_html()` - Removes HTML tags
+//! - `nfc()` - Normalizes unicode to NFC form
+//!
+//! ## Numeric Transformers
+//! - `round(n)` - Rounds to `n` decimal places
+//!
+//! ## Custom Transformers
+//! - `custom()` - Implement custom transformation logic
+
+use regex::Regex;
+use serde_json::{Number, Value};
+use unicode_normalization::UnicodeNormalization;
+use crate::traits::Transformer;
+
+/// Factory for creating value transformers
+pub struct Transform;
+impl Transform {
+    /// Removes leading and trailing whitespace from strings
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::transforms::Transform;
+    /// use validate_ro::traits::Transformer;
+    ///
+    /// let transformer = Transform::trim();
+    /// assert_eq!(transformer.transform(json!("  hello ")), json!("hello"));
+    /// ```
+    pub fn trim() -> impl Transformer {
+        map_string(|s| s.trim().to_string())
+    }
+
+    /// Converts strings to lowercase
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::transforms::Transform;
+    /// use validate_ro::traits::Transformer;
+    ///
+    /// let transformer = Transform::lowercase();
+    /// assert_eq!(transformer.transform(json!("User@Example.COM")), json!("user@example.com"));
+    /// ```
+    pub fn lowercase() -> impl Transformer {
+        map_string(|s| s.to_lowercase())
+    }
+
+    /// Converts strings to uppercase
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::transforms::Transform;
+    /// use validate_ro::traits::Transformer;
+    ///
+    /// let transformer = Transform::uppercase();
+    /// assert_eq!(transformer.transform(json!("ir")), json!("IR"));
+    /// ```
+    pub fn uppercase() -> impl Transformer {
+        map_string(|s| s.to_uppercase())
+    }
+
+    /// Trims strings and replaces every run of whitespace with a single space
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::transforms::Transform;
+    /// use validate_ro::traits::Transformer;
+    ///
+    /// let transformer = Transform::collapse_whitespace();
+    /// assert_eq!(transformer.transform(json!(" John \t  Doe ")), json!("John Doe"));
+    /// ```
+    pub fn collapse_whitespace() -> impl Transformer {
+        map_string(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+
+    /// Removes HTML tags from strings, keeping their text content
+    ///
+    /// Only `<` followed by a letter, `/` or `!` starts a tag, so text such as
+    /// "1 < 2 and 3 > 2" is kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::transforms::Transform;
+    /// use validate_ro::traits::Transformer;
+    ///
+    /// let transformer = Transform::strip_html();
+    /// assert_eq!(transformer.transform(json!("<b>bold</b> text")), json!("bold text"));
+    /// ```
+    pub fn strip_html() -> impl Transformer {
+        let re = Regex::new(r"<[a-zA-Z/!][^>]*>").unwrap();
+        map_string(move |s| re.replace_all(s, "").into_owned())
+    }
+
+    /// Normalizes strings to unicode Normalization Form C
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::transforms::Transform;
+    /// use validate_ro::traits::Transformer;
+    ///
+    /// let transformer = Transform::nfc();
+    /// assert_eq!(transformer.transform(json!("e\u{301}")), json!("\u{e9}"));
+    /// ```
+    pub fn nfc() -> impl Transformer {
+        map_string(|s| s.nfc().collect())
+    }
+
+    /// Rounds numbers to the given number of decimal places
+    ///
+    /// Rounding to 0 decimals produces an integer.
+    ///
+    /// # Arguments
+    ///
+    /// * `decimals` - Number of decimal places to keep
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use validate_ro::transforms::Transform;
+    /// use validate_ro::traits::Transformer;
+    ///
+    /// assert_eq!(Transform::round(2).transform(json!(3.14159)), json!(3.14));
+    /// assert_eq!(Transform::round(0).transform(json!(2.5)), json!(3));
+    /// ```
+    pub fn round(decimals: u32) -> impl Transformer {
+        move |value: Value| {
+            let num = match &value {
+                Value::Number(n) if n.is_f64() => n.as_f64().unwrap(),
+                _ => return value,
+            };
+            let factor = 10f64.powi(decimals as i32);
+            let rounded = (num * factor).round() / factor;
+            if decimals == 0 && rounded.abs() < i64::MAX as f64 {
+                return Value::from(rounded as i64);
+            }
+            Number::from_f64(rounded).map_or(value, Value::Number)
+        }
+    }
+
+    /// Creates custom transformer from closure
+    ///
+    /// # Arguments
+    ///
+    /// * `transformer` - Transformation function
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::{json, Value};
+    /// use validate_ro::transforms::Transform;
+    /// use validate_ro::traits::Transformer;
+    ///
+    /// let transformer = Transform::custom(|value| match value {
+    ///     Value::String(s) => Value::String(s.replace('-', "")),
+    ///     other => other,
+    /// });
+    /// assert_eq!(transformer.transform(json!("555-0100")), json!("5550100"));
+    /// ```
+    pub fn custom<F>(transformer: F) -> impl Transformer
+    where
+        F: Fn(Value) -> Value+Send+Sync+ 'static
+    {
+        transformer
+    }
+}
+
+/// Builds a transformer that only rewrites string values
+fn map_string<F>(f: F) -> impl Transformer
+where
+    F: Fn(&str) -> String+Send+Sync+ 'static
+{
+    move |value: Value| match value {
+        Value::String(s) => Value::String(f(&s)),
+        other => other,
+    }
+}
@@ -1,9 +1,11 @@
-use mongodb::bson::Bson;
+#[cfg(feature = "mongodb")]
+use mongodb::bson::{doc, Bson};
 use serde_json::{json, Value};
 use validate_ro::rules::Rule;
+use validate_ro::transforms::Transform;
 use validate_ro::traits::Validator;
-use validate_ro::{rules, FormValidator, Rules};
-use validate_ro::error::ValidationError;
+use validate_ro::{rules, FormValidator, Rules, Strictness};
+use validate_ro::error::{ValidationError, ValidationErrors};
 
 #[test]
 fn test_rules_validation() {
@@ -49,13 +51,14 @@ fn test_form_validator_success() {
         "age": 25
     });
 
-    let result = form_validator.validate(&form_data);
+    let result = form_validator.validate_json(&form_data);
     assert!(result.is_ok());
 
     let valid_data = result.unwrap();
-    assert_eq!(valid_data["username"], "testuser".into());
+    assert_eq!(Value::Object(valid_data), json!({"username": "testuser", "age": 25}));
 
-    assert_eq!(valid_data["age"], Bson::Int64(25));
+    #[cfg(feature = "mongodb")]
+    assert_eq!(form_validator.validate(&form_data).unwrap()["age"], Bson::Int64(25));
 }
 
 
@@ -70,15 +73,15 @@ fn test_form_validator_with_errors() {
         "password": "short"
     });
 
-    let result = form_validator.validate(&form_data);
+    let result = form_validator.validate_json(&form_data);
     assert!(result.is_err());
 
     let errors = result.unwrap_err();
     assert_eq!(errors.len(), 2);
 
 
-    assert!(matches!(errors.get("email").unwrap().get(0).unwrap(), ValidationError::EmailError(_)));
-    assert!(matches!(errors.get("password").unwrap().get(0).unwrap(), ValidationError::MinLengthError{..}));
+    assert!(matches!(errors.get("email").unwrap().first().unwrap(), ValidationError::EmailError(_)));
+    assert!(matches!(errors.get("password").unwrap().first().unwrap(), ValidationError::MinLengthError{..}));
 }
 
 #[test]
@@ -92,12 +95,12 @@ fn test_form_validator_missing_required_field() {
         // email is missing
     });
 
-    let result = form_validator.validate(&form_data);
+    let result = form_validator.validate_json(&form_data);
     assert!(result.is_err());
 
     let errors = result.unwrap_err();
     assert_eq!(errors.len(), 1);
-    assert!(matches!(errors.get("email").unwrap().get(0).unwrap(), ValidationError::Required));
+    assert!(matches!(errors.get("email").unwrap().first().unwrap(), ValidationError::Required));
 }
 
 #[test]
@@ -111,7 +114,7 @@ fn test_form_validator_break_on_first_error() {
         "password": "short"
     });
 
-    let result = form_validator.validate(&form_data);
+    let result = form_validator.validate_json(&form_data);
     assert!(result.is_err());
 
     // Should only return the first error
@@ -132,13 +135,13 @@ fn test_nested_rules_validation() {
             "notifications": true
         }
     });
-    assert!(form_validator.validate(&valid_data).is_ok());
+    assert!(form_validator.validate_json(&valid_data).is_ok());
     // Test with invalid nested data
     let invalid_data = json!({
         "user": "tu",  // too short
         "settings": {}  // missing notifications
     });
-    let result = form_validator.validate(&invalid_data);
+    let result = form_validator.validate_json(&invalid_data);
     assert!(result.is_err());
 
     let errors = result.unwrap_err();
@@ -173,18 +176,18 @@ fn test_custom_validator_in_form() {
     let valid_data = json!({
         "password": "SecurePass123"
     });
-    assert!(form_validator.validate(&valid_data).is_ok());
+    assert!(form_validator.validate_json(&valid_data).is_ok());
 
     // Test invalid password (no uppercase)
     let invalid_data = json!({
         "password": "weakpass123"
     });
-    let result = form_validator.validate(&invalid_data);
+    let result = form_validator.validate_json(&invalid_data);
     assert!(result.is_err());
     match result {
         Ok(_) => {}
         Err(errors) => {
-            assert!(matches!(errors.get("password").unwrap().get(0).unwrap(), ValidationError::Custom(_)));
+            assert!(matches!(errors.get("password").unwrap().first().unwrap(), ValidationError::Custom(_)));
         }
     }
 }
@@ -202,14 +205,504 @@ fn test_default_validator_in_form() {
     let valid_data = json!({
         "name": "Ali"
     });
-    let data=form_validator.validate(&valid_data);
+    let data=form_validator.validate_json(&valid_data);
 
     match data {
         Ok(d) => {
-            assert_eq!(d.get("active").unwrap(),&Bson::Boolean(false));
+            assert_eq!(d["active"], false);
         }
         Err(errors) => {
             panic!("error: {:?}",errors);
         }
     }
 }
+
+#[test]
+fn test_wildcard_array_paths() {
+    let form_validator = FormValidator::new()
+        .add("items.*.sku", Rules::new().add(Rule::required()).add(Rule::min_length(3)))
+        .add("tags.*", Rules::new().add(Rule::string()));
+
+    let valid_data = json!({
+        "items": [{"sku": "abc"}, {"sku": "def"}],
+        "tags": ["a", "b"]
+    });
+    let data = form_validator.validate_json(&valid_data).unwrap();
+    assert_eq!(Value::Object(data), valid_data);
+
+    let invalid_data = json!({
+        "items": [{"sku": "abc"}, {"sku": "de"}, {}, {"sku": "ghi"}],
+        "tags": ["a", 1]
+    });
+    let errors = form_validator.validate_json(&invalid_data).unwrap_err();
+    assert_eq!(errors.len(), 3);
+    assert!(matches!(errors.get("items.1.sku").unwrap()[0], ValidationError::MinLengthError{..}));
+    assert!(matches!(errors.get("items.2.sku").unwrap()[0], ValidationError::Required));
+    assert!(matches!(errors.get("tags.1").unwrap()[0], ValidationError::TypeError{..}));
+}
+
+#[test]
+fn test_indexed_array_paths() {
+    let form_validator = FormValidator::new()
+        .add("items.0.sku", Rules::new().add(Rule::required()));
+
+    assert!(form_validator.validate_json(&json!({"items": [{"sku": "abc"}]})).is_ok());
+
+    let errors = form_validator.validate_json(&json!({"items": [{"name": "abc"}]})).unwrap_err();
+    assert!(matches!(errors.get("items.0.sku").unwrap()[0], ValidationError::Required));
+}
+
+#[test]
+fn test_nested_output_document() {
+    let form_validator = FormValidator::new()
+        .add("name", Rules::new().add(Rule::required()))
+        .add("profile.age", Rules::new().add(Rule::integer()))
+        .add("profile.city", Rules::new().add(Rule::string()).default(json!("Tehran")));
+
+    let form_data = json!({"name": "Ali", "profile": {"age": 30}});
+    let data = form_validator.validate_json(&form_data).unwrap();
+    assert_eq!(Value::Object(data), json!({"name": "Ali", "profile": {"age": 30, "city": "Tehran"}}));
+    #[cfg(feature = "mongodb")]
+    {
+        let document = form_validator.validate(&form_data).unwrap();
+        assert_eq!(document.get_document("profile").unwrap(), &doc! {"age": 30_i64, "city": "Tehran"});
+    }
+
+    let flat = FormValidator::new()
+        .flat_output()
+        .add("profile.age", Rules::new().add(Rule::integer()));
+    let data = flat.validate_json(&json!({"profile": {"age": 30}})).unwrap();
+    assert_eq!(Value::Object(data), json!({"profile.age": 30}));
+}
+
+#[test]
+fn test_json_output() {
+    let form_validator = FormValidator::new()
+        .add("name", Rules::new().add(Rule::required()))
+        .add("profile.age", Rules::new().coerce().add(Rule::integer()))
+        .add("tags.*", Rule::string());
+
+    let data: serde_json::Map<String, Value> = form_validator
+        .validate_json(&json!({"name": "Ali", "profile": {"age": "30"}, "tags": ["a", "b"]}))
+        .unwrap();
+    assert_eq!(Value::Object(data), json!({"name": "Ali", "profile": {"age": 30}, "tags": ["a", "b"]}));
+}
+
+#[test]
+fn test_nested_output_follows_input_shape() {
+    // Numeric keys of objects stay object keys
+    let form_validator = FormValidator::new().add("limits.5", Rule::integer());
+    let data = form_validator.validate_json(&json!({"limits": {"5": 1}})).unwrap();
+    assert_eq!(Value::Object(data), json!({"limits": {"5": 1}}));
+
+    // Arrays are only built where the input has one
+    let form_validator = FormValidator::new().add("items.*.sku", Rule::string());
+    let data = form_validator.validate_json(&json!({"items": [{"sku": "a"}, {"sku": "b"}]})).unwrap();
+    assert_eq!(Value::Object(data), json!({"items": [{"sku": "a"}, {"sku": "b"}]}));
+
+    let form_validator = FormValidator::new()
+        .strictness(Strictness::Passthrough)
+        .add("a.*.x", Rule::integer());
+    let data = form_validator.validate_json(&json!({"a": {"20000000": {"y": 1}}})).unwrap();
+    assert_eq!(Value::Object(data), json!({"a": {"20000000": {"y": 1}}}));
+
+    // Indexes past the end of an input array are left out
+    let form_validator = FormValidator::new()
+        .add("items.*.sku", Rule::string())
+        .add("items.5.sku", Rule::string())
+        .add("items.4000000000.x", Rule::string());
+    let data = form_validator.validate_json(&json!({"items": [{"sku": "a"}]})).unwrap();
+    assert_eq!(Value::Object(data), json!({"items": [{"sku": "a"}]}));
+
+    // A validated scalar is not replaced by the values of its sub-paths
+    let form_validator = FormValidator::new()
+        .add("a", Rule::integer())
+        .add("a.b", Rule::string());
+    let data = form_validator.validate_json(&json!({"a": 5})).unwrap();
+    assert_eq!(Value::Object(data), json!({"a": 5}));
+}
+
+#[test]
+fn test_cross_field_rules_in_form() {
+    let form_validator = FormValidator::new()
+        .add("password", Rules::new().add(Rule::required()).add(Rule::confirmed()))
+        .add("items.*.max", Rules::new().add(Rule::gt_field("items.*.min")));
+
+    let valid_data = json!({
+        "password": "secret",
+        "password_confirmation": "secret",
+        "items": [{"min": 1, "max": 2}, {"min": 3, "max": 10}]
+    });
+    assert!(form_validator.validate_json(&valid_data).is_ok());
+
+    let invalid_data = json!({
+        "password": "secret",
+        "password_confirmation": "other",
+        "items": [{"min": 1, "max": 2}, {"min": 3, "max": 3}]
+    });
+    let errors = form_validator.validate_json(&invalid_data).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(errors.get("password").unwrap()[0], ValidationError::ConfirmedError));
+    assert!(matches!(errors.get("items.1.max").unwrap()[0], ValidationError::GtFieldError(_)));
+}
+
+#[test]
+fn test_conditional_presence_in_form() {
+    let form_validator = FormValidator::new()
+        .add("shipping.method", Rules::new().add(Rule::required()))
+        .add("shipping.address", Rules::new().add(Rule::required_unless("shipping.method", json!("pickup"))))
+        .add("vat_id", Rules::new().add(Rule::required_if("customer", json!("business"))).add(Rule::string()));
+
+    assert!(form_validator.validate_json(&json!({"shipping": {"method": "pickup"}, "customer": "private"})).is_ok());
+
+    let errors = form_validator
+        .validate_json(&json!({"shipping": {"method": "courier"}, "customer": "business"}))
+        .unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(errors.get("shipping.address").unwrap()[0], ValidationError::RequiredUnlessError{..}));
+    assert!(matches!(errors.get("vat_id").unwrap()[0], ValidationError::RequiredIfError{..}));
+}
+
+#[test]
+fn test_transformers_in_form() {
+    let form_validator = FormValidator::new()
+        .add("email", Rules::new()
+            .transform(Transform::trim())
+            .transform(Transform::lowercase())
+            .add(Rule::email(None)))
+        .add("username", Rules::new()
+            .transform(Transform::trim())
+            .add(Rule::min_length(3)))
+        .add("price", Rules::new().transform(Transform::round(2)));
+
+    let data = form_validator
+        .validate_json(&json!({"email": "  John@Example.COM ", "username": " abc ", "price": 9.999}))
+        .unwrap();
+    assert_eq!(Value::Object(data), json!({"email": "john@example.com", "username": "abc", "price": 10.0}));
+
+    // Validators see the transformed value
+    let errors = form_validator
+        .validate_json(&json!({"email": "john@example.com", "username": "  ab  "}))
+        .unwrap_err();
+    assert!(matches!(errors.get("username").unwrap()[0], ValidationError::MinLengthError{..}));
+}
+
+#[test]
+fn test_coercion() {
+    let form_validator = FormValidator::new()
+        .coerce()
+        .add("age", Rules::new().add(Rule::integer()).add(Rule::min_value(18.0)))
+        .add("price", Rules::new().add(Rule::float()))
+        .add("active", Rule::boolean())
+        .add("note", Rules::new().add(Rule::integer()))
+        .add("name", Rules::new().add(Rule::string()));
+
+    let form_data = json!({"age": "42", "price": "9", "active": "on", "note": "", "name": "007"});
+    let data = form_validator.validate_json(&form_data).unwrap();
+    assert_eq!(Value::Object(data), json!({"age": 42, "price": 9.0, "active": true, "note": null, "name": "007"}));
+    #[cfg(feature = "mongodb")]
+    {
+        let data = form_validator.validate(&form_data).unwrap();
+        assert_eq!(data.get("age").unwrap(), &Bson::Int64(42));
+        assert_eq!(data.get("price").unwrap(), &Bson::Double(9.0));
+    }
+
+    let errors = form_validator.validate_json(&json!({"age": "17", "active": "maybe"})).unwrap_err();
+    assert!(matches!(errors.get("age").unwrap()[0], ValidationError::MinValueError{..}));
+    assert!(matches!(errors.get("active").unwrap()[0], ValidationError::TypeError{..}));
+
+    // Without coercion strings are rejected
+    let strict = FormValidator::new().add("age", Rules::new().add(Rule::integer()));
+    assert!(strict.validate_json(&json!({"age": "42"})).is_err());
+
+    // Per-Rules coercion
+    let rules = Rules::new().coerce().add(Rule::boolean());
+    assert_eq!(rules.process(&json!("FALSE")).unwrap(), json!(false));
+}
+
+#[test]
+fn test_unknown_fields() {
+    let data = json!({
+        "name": "Ali",
+        "role": "admin",
+        "profile": {"age": 30, "extra": 1},
+        "items": [{"sku": "abc", "price": 0}],
+        "meta": {"any": {"thing": true}}
+    });
+    let build = |strictness| FormValidator::new()
+        .strictness(strictness)
+        .add("name", Rule::required())
+        .add("profile.age", Rule::integer())
+        .add("items.*.sku", Rule::string())
+        .add("meta", Rule::object());
+
+    // Strip drops unknown keys
+    let stripped = build(Strictness::Strip).validate_json(&data).unwrap();
+    assert_eq!(Value::Object(stripped), json!({
+        "name": "Ali",
+        "profile": {"age": 30},
+        "items": [{"sku": "abc"}],
+        "meta": {"any": {"thing": true}}
+    }));
+
+    // Reject reports every unknown key by its path
+    let errors = build(Strictness::Reject).validate_json(&data).unwrap_err();
+    assert_eq!(errors.len(), 3);
+    for path in ["role", "profile.extra", "items.0.price"] {
+        assert!(matches!(errors.get(path).unwrap()[0], ValidationError::UnknownField));
+    }
+
+    // Passthrough copies unknown keys into the output
+    let passed = build(Strictness::Passthrough).validate_json(&data).unwrap();
+    assert_eq!(passed["role"], "admin");
+    assert_eq!(passed["profile"], json!({"age": 30, "extra": 1}));
+    assert_eq!(passed["items"][0], json!({"sku": "abc", "price": 0}));
+}
+
+#[test]
+fn test_unknown_keys_with_dots() {
+    use validate_ro::messages::Messages;
+    use validate_ro::problem::ProblemDetails;
+
+    let data = json!({"profile": {"age": 20, "a.b": 1}, "profile.age": "evil"});
+    let build = |strictness| FormValidator::new()
+        .strictness(strictness)
+        .add("profile.age", rules![Rule::integer(), Rule::min_value(18.0)]);
+
+    // Dotted keys are reported under their escaped path, never under the
+    // path of a real field
+    let errors = build(Strictness::Reject).validate_json(&data).unwrap_err();
+    assert!(errors.get("profile.age").is_none());
+    assert!(errors.form_errors().is_empty());
+    let keys: Vec<&String> = errors.keys().collect();
+    assert_eq!(keys, [r"profile.a\.b", r"profile\.age"]);
+
+    let messages = Messages::english().render_all(&errors);
+    assert_eq!(messages[r"profile\.age"], ["profile.age is not allowed"]);
+    let problem = ProblemDetails::new().render(&errors);
+    assert!(problem["errors"]["/profile.age"].is_array());
+    assert!(problem["errors"]["/profile/a.b"].is_array());
+
+    // Passthrough leaves them out like Strip
+    let data = build(Strictness::Passthrough).validate_json(&data).unwrap();
+    assert_eq!(Value::Object(data), json!({"profile": {"age": 20}}));
+}
+
+#[test]
+fn test_nested_schemas() {
+    let address_validator = FormValidator::new()
+        .add("street", Rules::new().add(Rule::required()))
+        .add("zip", Rules::new().add(Rule::required()).add(Rule::length(5)));
+    let item_validator = FormValidator::new()
+        .add("sku", Rules::new().add(Rule::required()))
+        .add("qty", Rules::new().add(Rule::integer()).add(Rule::min_value(1.0)));
+
+    let form_validator = FormValidator::new()
+        .add("name", Rule::required())
+        .add("address", address_validator)
+        .add("line_items.*", item_validator);
+
+    let data = form_validator.validate_json(&json!({
+        "name": "Ali",
+        "address": {"street": "Main", "zip": "12345"},
+        "line_items": [{"sku": "a", "qty": 1}, {"sku": "b", "qty": 2}]
+    })).unwrap();
+    assert_eq!(data["address"], json!({"street": "Main", "zip": "12345"}));
+    assert_eq!(data["line_items"][1], json!({"sku": "b", "qty": 2}));
+
+    let errors = form_validator.validate_json(&json!({
+        "name": "Ali",
+        "address": {"street": "Main", "zip": "123"},
+        "line_items": [{"sku": "a", "qty": 1}, {"sku": "b", "qty": 1}, {"sku": "c", "qty": 0}, "bad"]
+    })).unwrap_err();
+    assert_eq!(errors.len(), 3);
+    assert!(matches!(errors.get("address.zip").unwrap()[0], ValidationError::LengthError{..}));
+    assert!(matches!(errors.get("line_items.2.qty").unwrap()[0], ValidationError::MinValueError{..}));
+    assert!(matches!(errors.get("line_items.3").unwrap()[0], ValidationError::TypeError{..}));
+}
+
+#[test]
+fn test_form_validator_as_validator() {
+    let address_validator = FormValidator::new()
+        .add("zip", Rules::new().add(Rule::required()));
+    let rules = Rules::new().add(Rule::required()).add(address_validator);
+
+    assert!(rules.validate(&json!({"zip": "12345"})).is_ok());
+    match rules.validate(&json!({})) {
+        Err(ValidationError::Nested(errors)) => {
+            assert!(matches!(errors.get("zip").unwrap()[0], ValidationError::Required));
+        }
+        other => panic!("expected nested error, got {:?}", other),
+    }
+
+    // Inside `Rules` the schema builds the field's output, stripping extra keys
+    let address = FormValidator::new().add("zip", Rule::string());
+    let form_validator = FormValidator::new().add("addr", rules![Rule::required(), address]);
+    let data = form_validator.validate_json(&json!({"addr": {"zip": "1", "junk": 2}})).unwrap();
+    assert_eq!(Value::Object(data), json!({"addr": {"zip": "1"}}));
+    assert!(form_validator.validate_json(&json!({})).is_err());
+}
+
+#[test]
+fn test_validation_errors_report() {
+    let address = FormValidator::new().add("zip", rules![Rule::required()]);
+    let form_validator = FormValidator::new()
+        .add("data", rules![Rule::required()])
+        .add("contact", Rules::new().add(FormValidator::new().add("email", Rule::email(None))))
+        .add("address", address);
+
+    let mut errors = form_validator
+        .validate_json(&json!({"contact": {"email": "nope"}, "address": {}}))
+        .unwrap_err();
+    let keys: Vec<&String> = errors.keys().collect();
+    assert_eq!(keys, ["data", "contact", "address.zip"]);
+    // Paths reach into nested errors
+    assert!(matches!(errors["contact.email"][0], ValidationError::EmailError(_)));
+    assert!(errors.get("contact.phone").is_none());
+    assert!(errors.form_errors().is_empty());
+
+    let mut more = ValidationErrors::new();
+    more.add("data", ValidationError::UnknownField);
+    more.add_form_error(ValidationError::Custom("Too many requests".to_string()));
+    errors.merge(more);
+    assert_eq!(errors["data"], [ValidationError::Required, ValidationError::UnknownField]);
+    assert_eq!(errors.form_errors(), [ValidationError::Custom("Too many requests".to_string())]);
+    assert_eq!(errors.len(), 4);
+
+    let json = serde_json::to_value(&errors).unwrap();
+    assert_eq!(json["data"], json!(["required_error", "unknown_field_error"]));
+    assert_eq!(json[""], json!([["validate_error", ["Too many requests"]]]));
+    assert_eq!(serde_json::from_value::<ValidationErrors>(json).unwrap(), errors);
+
+    assert_eq!(
+        errors.to_string(),
+        "data: value is required\ndata: value is not allowed\ncontact: value is invalid\naddress.zip: value is required\nToo many requests"
+    );
+}
+
+#[test]
+fn test_collect_all_errors_in_field() {
+    let form_validator = FormValidator::new()
+        .add("password", Rules::new()
+            .collect_all()
+            .add(Rule::min_length(8))
+            .add(Rule::regex(r"[0-9]", None).unwrap())
+            .add(Rule::not_in_values(vec![json!("abc")])))
+        .add("username", Rules::new()
+            .add(Rule::min_length(8))
+            .add(Rule::regex(r"[0-9]", None).unwrap()));
+
+    let errors = form_validator.validate_json(&json!({"password": "abc", "username": "abc"})).unwrap_err();
+    let password_errors = errors.get("password").unwrap();
+    assert_eq!(password_errors.len(), 3);
+    assert!(matches!(password_errors[0], ValidationError::MinLengthError{..}));
+    assert!(matches!(password_errors[1], ValidationError::RegexError(_)));
+    assert!(matches!(password_errors[2], ValidationError::NotInError(_)));
+
+    // Bail is the default
+    assert_eq!(errors.get("username").unwrap().len(), 1);
+
+    // Nested chains keep all of their errors
+    let inner = || Rules::new()
+        .collect_all()
+        .add(Rule::min_length(3))
+        .add(Rule::regex(r"[0-9]", None).unwrap());
+    let outer = Rules::new().add(Rule::string()).add(inner());
+    let errors = outer.process(&json!("ab")).unwrap_err();
+    assert!(matches!(errors[..], [ValidationError::MinLengthError { .. }, ValidationError::RegexError(_)]));
+    let form_validator = FormValidator::new().add("code", outer);
+    assert_eq!(form_validator.validate_json(&json!({"code": "ab"})).unwrap_err()["code"].len(), 2);
+
+    // Through the `Validator` trait they are returned as one nested error
+    match inner().validate(&json!("ab")) {
+        Err(ValidationError::Nested(nested)) => assert_eq!(nested.form_errors().len(), 2),
+        other => panic!("expected nested errors, got {:?}", other),
+    }
+    assert!(matches!(inner().validate(&json!("a1")), Err(ValidationError::MinLengthError { .. })));
+}
+
+#[test]
+fn test_field_order_is_preserved() {
+    let form_validator = FormValidator::new()
+        .add("zeta", Rule::required())
+        .add("alpha", Rule::required())
+        .add("profile.name", Rule::required())
+        .add("middle", Rule::required())
+        .add("profile", Rule::object());
+
+    let data = form_validator
+        .validate_json(&json!({"middle": 1, "alpha": 2, "zeta": 3, "profile": {"name": "x"}}))
+        .unwrap();
+    let keys: Vec<&String> = data.keys().collect();
+    assert_eq!(keys, ["zeta", "alpha", "profile", "middle"]);
+
+    let errors = form_validator.validate_json(&json!({})).unwrap_err();
+    let keys: Vec<&String> = errors.keys().collect();
+    assert_eq!(keys, ["zeta", "alpha", "profile.name", "middle"]);
+
+    // break_on_error always reports the first field added
+    let form_validator = FormValidator::new().break_on_error()
+        .add("zeta", Rule::required())
+        .add("alpha", Rule::required());
+    for _ in 0..10 {
+        let errors = form_validator.validate_json(&json!({})).unwrap_err();
+        assert!(errors.contains_key("zeta"));
+    }
+}
+
+#[test]
+#[cfg(feature = "mongodb")]
+fn test_bson_input() {
+    use mongodb::bson::{oid::ObjectId, spec::BinarySubtype, Binary, DateTime, Decimal128};
+
+    let form_validator = FormValidator::new()
+        .add("_id", rules![Rule::required(), Rule::object_id()])
+        .add("created_at", Rule::datetime())
+        .add("price", Rule::decimal128())
+        .add("avatar", Rule::binary())
+        .add("stock", Rules::new().add(Rule::int32()).add(Rule::min_value(0.0)))
+        .add("views", Rule::int64())
+        .add("tags.*", Rules::new().transform(Transform::lowercase()));
+
+    let id = ObjectId::new();
+    let document = doc! {
+        "_id": id,
+        "created_at": DateTime::from_millis(1_700_000_000_000),
+        "price": "9.99".parse::<Decimal128>().unwrap(),
+        "avatar": Binary { subtype: BinarySubtype::Generic, bytes: vec![1, 2, 3] },
+        "stock": 5,
+        "views": 42_i64,
+        "tags": ["A", "B"],
+    };
+    let data = form_validator.validate_bson(&document).unwrap();
+    assert_eq!(data.get_object_id("_id").unwrap(), id);
+    assert_eq!(data.get("created_at"), document.get("created_at"));
+    assert_eq!(data.get("price"), document.get("price"));
+    assert_eq!(data.get("avatar"), document.get("avatar"));
+    assert_eq!(data.get("stock"), Some(&Bson::Int32(5)));
+    assert_eq!(data.get("views"), Some(&Bson::Int64(42)));
+    assert_eq!(data.get_array("tags").unwrap(), &vec![Bson::String("a".into()), Bson::String("b".into())]);
+
+    let errors = form_validator
+        .validate_bson(&doc! {"_id": "not-an-id", "stock": 5_000_000_000_i64, "views": 1.5})
+        .unwrap_err();
+    let keys: Vec<&String> = errors.keys().collect();
+    assert_eq!(keys, ["_id", "stock", "views"]);
+
+    // Integer widths are those of the bson input
+    let form_validator = FormValidator::new()
+        .add("a", Rule::int64())
+        .add("b", Rule::int32())
+        .add("items.*.n", Rule::int32());
+    let errors = form_validator
+        .validate_bson(&doc! {"a": 5_i32, "b": 5_i64, "items": [{"n": 1_i32}, {"n": 2_i64}]})
+        .unwrap_err();
+    let keys: Vec<&String> = errors.keys().collect();
+    assert_eq!(keys, ["a", "b", "items.1.n"]);
+    assert!(form_validator.validate_bson(&doc! {"a": 5_i64, "b": 5_i32}).is_ok());
+    assert!(Rules::new().add(Rule::int32()).process_bson(&Bson::Int64(5)).is_err());
+
+    // ObjectId strings can be coerced
+    let rule = Rules::new().coerce().add(Rule::object_id());
+    assert_eq!(rule.process_bson(&Bson::String(id.to_hex())).unwrap(), Bson::ObjectId(id));
+}
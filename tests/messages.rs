@@ -0,0 +1,145 @@
+use serde_json::json;
+use validate_ro::error::{ValidationError, ValidationErrors};
+use validate_ro::messages::Messages;
+use validate_ro::rules::Rule;
+use validate_ro::{rules, FormValidator};
+
+#[test]
+fn test_english_messages() {
+    let messages = Messages::english();
+
+    assert_eq!(messages.render("email", &ValidationError::Required), "email is required");
+    assert_eq!(
+        messages.render("user.first_name", &ValidationError::MaxLengthError { expected: 20, got: 25 }),
+        "first name must not be longer than 20 characters"
+    );
+    assert_eq!(
+        messages.render("items.0.qty", &ValidationError::MinValueError { expected: 1.0, got: 0.0 }),
+        "qty must be at least 1"
+    );
+    assert_eq!(
+        messages.render("ends_at", &ValidationError::GtFieldError("starts_at".to_string())),
+        "ends at must be greater than starts_at"
+    );
+    assert_eq!(messages.render("code", &ValidationError::Custom("Code is expired".to_string())), "Code is expired");
+
+    // Substituted values are not scanned for placeholders again
+    assert_eq!(
+        messages.render("note", &ValidationError::RequiredIfError { field: ":value".to_string(), value: ":field".to_string() }),
+        "note is required when :value is :field"
+    );
+    assert_eq!(messages.render("code", &ValidationError::Custom("Use :field".to_string())), "Use :field");
+
+    // Nested forms are flattened to prefixed paths
+    let address = FormValidator::new().add("zip", rules![Rule::required()]);
+    let form_validator = FormValidator::new()
+        .add("name", rules![Rule::required(), Rule::min_length(3)])
+        .add("address", address);
+    let errors = form_validator.validate_json(&json!({"name": "Al", "address": {}})).unwrap_err();
+    let rendered = messages.render_all(&errors);
+    assert_eq!(rendered["name"], ["name must be at least 3 characters"]);
+    assert_eq!(rendered["address.zip"], ["zip is required"]);
+}
+
+#[test]
+fn test_custom_catalogs() {
+    let messages = Messages::from_json(r#"{
+        "required_error": ":field est obligatoire",
+        "min_len_error": ":field doit contenir au moins :min caractères"
+    }"#)
+    .unwrap()
+    .attribute("password", "Le mot de passe");
+
+    assert_eq!(messages.render("password", &ValidationError::Required), "Le mot de passe est obligatoire");
+    assert_eq!(
+        messages.render("password", &ValidationError::MinLengthError { expected: 8, got: 3 }),
+        "Le mot de passe doit contenir au moins 8 caractères"
+    );
+    // Missing codes fall back to English
+    assert_eq!(messages.render("email", &ValidationError::UniqueError), "email has already been taken");
+
+    let messages = Messages::english().set("unique_error", ":field is in use");
+    assert_eq!(messages.render("email", &ValidationError::UniqueError), "email is in use");
+
+    assert!(Messages::from_json("[]").is_err());
+    assert!(Messages::load("catalog.yaml").is_err());
+
+    #[cfg(feature = "toml")]
+    {
+        let messages = Messages::from_toml(r#"required_error = ":field ist erforderlich""#).unwrap();
+        assert_eq!(messages.render("name", &ValidationError::Required), "name ist erforderlich");
+    }
+}
+
+#[test]
+fn test_custom_error_messages() {
+    use validate_ro::Rules;
+
+    let form_validator = FormValidator::new()
+        .add("password", Rules::new()
+            .add(Rule::required())
+            .add(Rule::min_length(8)).message("password too short (:min characters minimum)"))
+        .add("code", rules![Rule::required(), Rule::length(6)])
+        .message("code", "code_invalid");
+
+    let errors = form_validator.validate_json(&json!({"password": "abc", "code": "12"})).unwrap_err();
+    let error = &errors["password"][0];
+    assert!(matches!(error, ValidationError::Message { error, .. } if matches!(**error, ValidationError::MinLengthError { .. })));
+    assert_eq!(serde_json::to_value(error).unwrap(), json!(["message_error", ["password too short (:min characters minimum)", ["min_len_error", [8, 3]]]]));
+
+    // Messages are rendered with the parameters of the replaced error, or
+    // looked up as catalog keys
+    let messages = Messages::english().set("code_invalid", ":field must be a 6 digit code");
+    let rendered = messages.render_all(&errors);
+    assert_eq!(rendered["password"], ["password too short (8 characters minimum)"]);
+    assert_eq!(rendered["code"], ["code must be a 6 digit code"]);
+
+    // Only the chosen validator's errors are replaced
+    let errors = form_validator.validate_json(&json!({"code": "123456"})).unwrap_err();
+    assert!(matches!(errors["password"][0], ValidationError::Required));
+}
+
+#[test]
+fn test_error_round_trip() {
+    let mut nested = ValidationErrors::new();
+    nested.add_all("zip", [ValidationError::Required, ValidationError::LengthError { expected: 5, got: 3 }]);
+    let errors = vec![
+        ValidationError::Required,
+        ValidationError::TypeError { expected: "string".to_string(), got: "number".to_string() },
+        ValidationError::MinValueError { expected: 1.5, got: 0.0 },
+        ValidationError::ExtensionError(vec!["png".to_string(), "jpg".to_string()]),
+        ValidationError::FileSizeError { min: 1, max: 1024 },
+        ValidationError::RequiredIfError { field: "kind".to_string(), value: "\"company\"".to_string() },
+        ValidationError::RequiredWithError(vec!["a".to_string(), "b".to_string()]),
+        ValidationError::Nested(nested),
+        ValidationError::Timeout,
+        ValidationError::Required.with_message("required!"),
+        ValidationError::Custom("Code is expired".to_string()),
+    ];
+    for error in errors {
+        let json = serde_json::to_value(&error).unwrap();
+        let parsed: ValidationError = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(parsed, error);
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), json);
+    }
+
+    // Store failures keep their code but lose their cause
+    let parsed: ValidationError = serde_json::from_value(json!("store_error")).unwrap();
+    assert!(matches!(parsed, ValidationError::Store(_)));
+
+    assert!(serde_json::from_value::<ValidationError>(json!("nope_error")).is_err());
+    assert!(serde_json::from_value::<ValidationError>(json!(["min_len_error", ["8", 3]])).is_err());
+    assert!(serde_json::from_value::<ValidationError>(json!(["min_len_error", [8, 3], 1])).is_err());
+}
+
+#[test]
+fn test_error_display() {
+    assert_eq!(ValidationError::Required.to_string(), "value is required");
+    assert_eq!(ValidationError::MinLengthError { expected: 8, got: 3 }.to_string(), "value must be at least 8 characters");
+    assert_eq!(ValidationError::Custom("Code is expired".to_string()).to_string(), "Code is expired");
+
+    let error = ValidationError::MinLengthError { expected: 8, got: 3 }.with_message("too short (:min minimum)");
+    assert_eq!(error.to_string(), "too short (8 minimum)");
+    let source = std::error::Error::source(&error).unwrap();
+    assert_eq!(source.to_string(), "value must be at least 8 characters");
+}
@@ -0,0 +1,70 @@
+use serde_json::json;
+use validate_ro::error::ValidationError;
+use validate_ro::messages::Messages;
+use validate_ro::problem::{PathStyle, ProblemDetails};
+use validate_ro::rules::Rule;
+use validate_ro::{rules, FormValidator, Rules};
+
+#[test]
+fn test_problem_details() {
+    let item = FormValidator::new().add("qty", rules![Rule::integer(), Rule::min_value(1.0)]);
+    let form_validator = FormValidator::new()
+        .add("name", rules![Rule::required()])
+        .add("items.*", item)
+        .add("a/b~c", Rule::email(None));
+
+    let mut errors = form_validator
+        .validate_json(&json!({"items": [{"qty": 2}, {"qty": 0}], "a/b~c": "nope"}))
+        .unwrap_err();
+    errors.add_form_error(ValidationError::Custom("Too many orders".to_string()));
+
+    let problem = ProblemDetails::new().render(&errors);
+    assert_eq!(problem, json!({
+        "type": "about:blank",
+        "title": "Unprocessable Content",
+        "status": 422,
+        "errors": {
+            "/name": [{"code": "required_error", "detail": "name is required"}],
+            "/items/1/qty": [{"code": "min_error", "detail": "qty must be at least 1"}],
+            "/a~1b~0c": [{"code": "email_error", "detail": "a/b~c must be a valid email address"}],
+            "": [{"code": "validate_error", "detail": "Too many orders"}]
+        }
+    }));
+
+    let problem = ProblemDetails::new()
+        .type_uri("https://example.com/problems/validation")
+        .title("Invalid order")
+        .status(400)
+        .detail("The order has invalid fields")
+        .path_style(PathStyle::Dotted)
+        .messages(Messages::english().set("required_error", ":field is missing"))
+        .render(&errors);
+    assert_eq!(problem["type"], "https://example.com/problems/validation");
+    assert_eq!(problem["title"], "Invalid order");
+    assert_eq!(problem["status"], 400);
+    assert_eq!(problem["detail"], "The order has invalid fields");
+    assert_eq!(problem["errors"]["name"], json!([{"code": "required_error", "detail": "name is missing"}]));
+    assert_eq!(problem["errors"]["items.1.qty"][0]["code"], "min_error");
+
+    // Custom messages keep the code of the replaced error
+    let form_validator = FormValidator::new()
+        .add("password", Rules::new().add(Rule::min_length(8)).message("password too short"));
+    let errors = form_validator.validate_json(&json!({"password": "abc"})).unwrap_err();
+    let problem = ProblemDetails::new().render(&errors);
+    assert_eq!(problem["errors"]["/password"], json!([{"code": "min_len_error", "detail": "password too short"}]));
+}
+
+#[test]
+fn test_problem_details_nested_errors() {
+    // A form inside `Rules` reports a single `ValidationError::Nested`
+    let address = FormValidator::new().add("zip", rules![Rule::required(), Rule::length(5)]);
+    let form_validator = FormValidator::new().add("address", Rules::new().add(address));
+
+    let errors = form_validator.validate_json(&json!({"address": {"zip": "123"}})).unwrap_err();
+    assert!(matches!(errors["address"][0], ValidationError::Nested(_)));
+
+    let problem = ProblemDetails::new().render(&errors);
+    assert_eq!(problem["errors"], json!({
+        "/address/zip": [{"code": "len_error", "detail": "zip must have a length of 5"}]
+    }));
+}
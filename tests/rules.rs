@@ -1,7 +1,7 @@
 use validate_ro::rules::*;
 use serde_json::{json, Value};
 use validate_ro::error::ValidationError;
-use validate_ro::traits::Validator;
+use validate_ro::traits::{Context, Validator};
 
 #[test]
 fn test_required() {
@@ -197,3 +197,75 @@ fn test_custom_validator() {
     assert!(custom_validator.validate(&json!("short")).is_err());
     assert!(custom_validator.validate(&Value::Null).is_ok());
 }
+
+#[test]
+fn test_cross_field() {
+    let data = json!({
+        "password": "secret",
+        "password_confirmation": "secret",
+        "start": 5,
+        "start_date": "2024-01-10"
+    });
+
+    let same = Rule::same("password");
+    assert!(same.validate_with_context(&json!("secret"), &Context::new(&data, "repeat")).is_ok());
+    assert!(matches!(same.validate_with_context(&json!("other"), &Context::new(&data, "repeat")), Err(ValidationError::SameError(_))));
+    assert!(same.validate(&json!("secret")).is_err()); // needs form context
+
+    let different = Rule::different("password");
+    assert!(different.validate_with_context(&json!("other"), &Context::new(&data, "new")).is_ok());
+    assert!(different.validate_with_context(&json!("secret"), &Context::new(&data, "new")).is_err());
+
+    let confirmed = Rule::confirmed();
+    assert!(confirmed.validate_with_context(&json!("secret"), &Context::new(&data, "password")).is_ok());
+    assert!(matches!(confirmed.validate_with_context(&json!("secret"), &Context::new(&data, "pin")), Err(ValidationError::ConfirmedError)));
+
+    let gt = Rule::gt_field("start");
+    assert!(gt.validate_with_context(&json!(6), &Context::new(&data, "end")).is_ok());
+    assert!(matches!(gt.validate_with_context(&json!(5), &Context::new(&data, "end")), Err(ValidationError::GtFieldError(_))));
+    assert!(gt.validate_with_context(&json!("6"), &Context::new(&data, "end")).is_err());
+    assert!(Rule::gt_field("missing").validate_with_context(&json!(1), &Context::new(&data, "end")).is_ok());
+
+    let lt = Rule::lt_field("start_date");
+    assert!(lt.validate_with_context(&json!("2024-01-09"), &Context::new(&data, "end_date")).is_ok());
+    assert!(matches!(lt.validate_with_context(&json!("2024-02-01"), &Context::new(&data, "end_date")), Err(ValidationError::LtFieldError(_))));
+    assert!(lt.validate_with_context(&Value::Null, &Context::new(&data, "end_date")).is_ok());
+}
+
+#[test]
+fn test_conditional_presence() {
+    let data = json!({"type": "business", "phone": "555"});
+    let ctx = Context::new(&data, "field");
+
+    let required_if = Rule::required_if("type", json!("business"));
+    assert!(matches!(required_if.validate_with_context(&Value::Null, &ctx), Err(ValidationError::RequiredIfError{..})));
+    assert!(required_if.validate_with_context(&json!("x"), &ctx).is_ok());
+    assert!(Rule::required_if("type", json!("personal")).validate_with_context(&Value::Null, &ctx).is_ok());
+
+    let required_unless = Rule::required_unless("type", json!("personal"));
+    assert!(matches!(required_unless.validate_with_context(&Value::Null, &ctx), Err(ValidationError::RequiredUnlessError{..})));
+    assert!(Rule::required_unless("type", json!("business")).validate_with_context(&Value::Null, &ctx).is_ok());
+
+    let required_with = Rule::required_with(&["phone", "fax"]);
+    assert!(matches!(required_with.validate_with_context(&Value::Null, &ctx), Err(ValidationError::RequiredWithError(_))));
+    assert!(Rule::required_with(&["fax"]).validate_with_context(&Value::Null, &ctx).is_ok());
+
+    let required_with_all = Rule::required_with_all(&["phone", "type"]);
+    assert!(matches!(required_with_all.validate_with_context(&Value::Null, &ctx), Err(ValidationError::RequiredWithAllError(_))));
+    assert!(Rule::required_with_all(&["phone", "fax"]).validate_with_context(&Value::Null, &ctx).is_ok());
+
+    let required_without = Rule::required_without(&["phone", "fax"]);
+    assert!(matches!(required_without.validate_with_context(&Value::Null, &ctx), Err(ValidationError::RequiredWithoutError(_))));
+    assert!(Rule::required_without(&["phone"]).validate_with_context(&Value::Null, &ctx).is_ok());
+
+    let prohibited_if = Rule::prohibited_if("type", json!("business"));
+    assert!(matches!(prohibited_if.validate_with_context(&json!("x"), &ctx), Err(ValidationError::ProhibitedIfError{..})));
+    assert!(prohibited_if.validate_with_context(&Value::Null, &ctx).is_ok());
+
+    // Without the form only values the rule could never reject pass
+    let rules = validate_ro::Rules::new().add(Rule::string()).add(Rule::required_with(&["phone"]));
+    assert_eq!(rules.process(&json!("x")).unwrap(), json!("x"));
+    assert!(rules.process(&Value::Null).is_err());
+    assert!(prohibited_if.validate(&Value::Null).is_ok());
+    assert!(prohibited_if.validate(&json!("x")).is_err());
+}
@@ -0,0 +1,512 @@
+use std::error::Error;
+#[cfg(feature = "mongodb")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+use async_trait::async_trait;
+use serde_json::json;
+use validate_ro::error::AsyncValidationError;
+use validate_ro::store::{MemoryStore, Query, Store, StoreError};
+
+struct FailingStore;
+
+#[async_trait]
+impl Store for FailingStore {
+    async fn count(&self, _query: &Query) -> Result<u64, StoreError> {
+        Err(StoreError::new("connection refused"))
+    }
+}
+
+/// Store counting the `count` and `distinct` queries made to it
+#[cfg(feature = "mongodb")]
+struct CountingStore {
+    inner: MemoryStore,
+    distinct: bool,
+    counts: AtomicUsize,
+    distincts: AtomicUsize,
+}
+
+#[cfg(feature = "mongodb")]
+impl CountingStore {
+    fn new(inner: MemoryStore) -> Self {
+        Self { inner, distinct: true, counts: AtomicUsize::new(0), distincts: AtomicUsize::new(0) }
+    }
+
+    fn queries(&self) -> (usize, usize) {
+        (self.counts.load(Ordering::SeqCst), self.distincts.load(Ordering::SeqCst))
+    }
+}
+
+#[cfg(feature = "mongodb")]
+#[async_trait]
+impl Store for CountingStore {
+    async fn count(&self, query: &Query) -> Result<u64, StoreError> {
+        self.counts.fetch_add(1, Ordering::SeqCst);
+        self.inner.count(query).await
+    }
+
+    async fn distinct(&self, query: &Query, field: &str) -> Result<Vec<serde_json::Value>, StoreError> {
+        if !self.distinct {
+            return Err(StoreError::unsupported("distinct"));
+        }
+        self.distincts.fetch_add(1, Ordering::SeqCst);
+        self.inner.distinct(query, field).await
+    }
+}
+
+fn users() -> MemoryStore {
+    MemoryStore::new().with_collection("users", vec![
+        json!({"_id": {"$oid": "65a1b2c3d4e5f60718293a4b"}, "email": "a@example.com", "age": 30, "tags": ["admin", "dev"]}),
+        json!({"_id": {"$oid": "65a1b2c3d4e5f60718293a4c"}, "email": "b@example.com", "age": 25.0, "profile": {"city": "Paris"}}),
+    ])
+}
+
+#[tokio::test]
+async fn test_memory_store_filters() {
+    let store = users();
+
+    // Eq
+    assert_eq!(store.count(&Query::new("users").eq("email", json!("a@example.com"))).await.unwrap(), 1);
+    assert_eq!(store.count(&Query::new("users").eq("email", json!("c@example.com"))).await.unwrap(), 0);
+
+    // Numbers compare by value
+    assert_eq!(store.count(&Query::new("users").eq("age", json!(25))).await.unwrap(), 1);
+    // Integers beyond f64 precision compare exactly
+    let ids = MemoryStore::new().with_collection("ids", vec![json!({"_id": 9007199254740992_i64})]);
+    assert_eq!(ids.count(&Query::new("ids").eq("_id", json!(9007199254740993_i64))).await.unwrap(), 0);
+    assert_eq!(ids.count(&Query::new("ids").eq("_id", json!(9007199254740992_u64))).await.unwrap(), 1);
+    assert_eq!(ids.count(&Query::new("ids").eq("_id", json!(9007199254740992.0))).await.unwrap(), 1);
+
+    // Nested paths and array fields
+    assert_eq!(store.count(&Query::new("users").eq("profile.city", json!("Paris"))).await.unwrap(), 1);
+    assert_eq!(store.count(&Query::new("users").eq("tags", json!("dev"))).await.unwrap(), 1);
+
+    // Missing fields equal null
+    assert_eq!(store.count(&Query::new("users").eq("profile", json!(null))).await.unwrap(), 1);
+
+    // Ne and In
+    let query = Query::new("users")
+        .eq("email", json!("a@example.com"))
+        .ne("_id", json!({"$oid": "65a1b2c3d4e5f60718293a4b"}));
+    assert_eq!(store.count(&query).await.unwrap(), 0);
+    let query = Query::new("users").is_in("email", vec![json!("a@example.com"), json!("b@example.com")]);
+    assert_eq!(store.count(&query).await.unwrap(), 2);
+
+    // Unknown collections are empty
+    assert_eq!(store.count(&Query::new("posts")).await.unwrap(), 0);
+}
+
+#[cfg(feature = "mongodb")]
+#[tokio::test]
+async fn test_unique_with_memory_store() {
+    use mongodb::bson::oid::ObjectId;
+    use validate_ro::error::ValidationError;
+    use validate_ro::rules::Rule;
+    use validate_ro::FormValidator;
+
+    let mut store = users();
+    store.insert("users", json!({"email": "c@example.com"}));
+
+    let validator = FormValidator::new()
+        .add("email", Rule::unique("users", "email", None));
+
+    let error = validator.validate_async(&store, &json!({"email": "a@example.com"})).await.unwrap_err();
+    let errors = error.validation_errors().unwrap();
+    assert!(matches!(errors.get("email").unwrap().first().unwrap(), ValidationError::UniqueError));
+    assert!(validator.validate_async(&store, &json!({"email": "c@example.com"})).await.is_err());
+    assert!(validator.validate_async(&store, &json!({"email": "new@example.com"})).await.is_ok());
+
+    // Excluding the document being updated
+    let id = ObjectId::parse_str("65a1b2c3d4e5f60718293a4b").unwrap();
+    let validator = FormValidator::new()
+        .add("email", Rule::unique("users", "email", Some(id)));
+    assert!(validator.validate_async(&store, &json!({"email": "a@example.com"})).await.is_ok());
+    assert!(validator.validate_async(&store, &json!({"email": "b@example.com"})).await.is_err());
+}
+
+#[tokio::test]
+async fn test_exists_with_memory_store() {
+    use validate_ro::error::ValidationError;
+    use validate_ro::rules::Rule;
+    use validate_ro::store::Filter;
+    use validate_ro::traits::Validator;
+
+    let store = MemoryStore::new().with_collection("categories", vec![
+        json!({"_id": {"$oid": "65a1b2c3d4e5f60718293a4b"}, "slug": "books"}),
+        json!({"_id": {"$oid": "65a1b2c3d4e5f60718293a4c"}, "slug": "music", "deleted": true}),
+    ]);
+
+    let validator = Rule::exists("categories", "slug");
+    assert!(validator.validate_async(&store, &json!("books")).await.is_ok());
+    assert!(validator.validate_async(&store, &json!(null)).await.is_ok());
+    assert!(matches!(validator.validate_async(&store, &json!("films")).await, Err(ValidationError::ExistsError)));
+    assert!(matches!(validator.validate_async(&store, &json!({"slug": "books"})).await, Err(ValidationError::TypeError { .. })));
+
+    // ObjectId strings and arrays
+    let validator = Rule::exists("categories", "_id").object_id();
+    assert!(validator.validate_async(&store, &json!("65a1b2c3d4e5f60718293a4b")).await.is_ok());
+    assert!(validator.validate_async(&store, &json!(["65a1b2c3d4e5f60718293a4b", "65a1b2c3d4e5f60718293a4c"])).await.is_ok());
+    assert!(validator.validate_async(&store, &json!(["65a1b2c3d4e5f60718293a4b", "65a1b2c3d4e5f60718293a4d"])).await.is_err());
+    assert!(validator.validate_async(&store, &json!("not-an-id")).await.is_err());
+
+    // Extra filter
+    let validator = Rule::exists("categories", "slug")
+        .filter(Filter::Ne("deleted".to_string(), json!(true)));
+    assert!(validator.validate_async(&store, &json!("books")).await.is_ok());
+    assert!(validator.validate_async(&store, &json!("music")).await.is_err());
+}
+
+#[cfg(feature = "mongodb")]
+#[tokio::test]
+async fn test_bson_lookups() {
+    use mongodb::bson::{doc, oid::ObjectId};
+    use validate_ro::error::ValidationError;
+    use validate_ro::rules::Rule;
+    use validate_ro::{FormValidator, Rules};
+
+    let books = ObjectId::parse_str("65a1b2c3d4e5f60718293a4b").unwrap();
+    let store = MemoryStore::new()
+        .with_collection("categories", vec![json!({"_id": {"$oid": books.to_hex()}})])
+        .with_collection("posts", vec![json!({"category": {"$oid": books.to_hex()}})]);
+
+    // ObjectIds of bson input reach the store as extended JSON
+    let validator = FormValidator::new().add("cat", Rule::exists("categories", "_id"));
+    assert!(validator.validate_bson_async(&store, &doc! {"cat": books}).await.is_ok());
+    let error = validator.validate_bson_async(&store, &doc! {"cat": ObjectId::new()}).await.unwrap_err();
+    assert!(matches!(error.validation_errors().unwrap()["cat"][0], ValidationError::ExistsError));
+
+    let validator = FormValidator::new().add("category", Rule::unique("posts", "category", None));
+    let error = validator.validate_bson_async(&store, &doc! {"category": books}).await.unwrap_err();
+    assert!(matches!(error.validation_errors().unwrap()["category"][0], ValidationError::UniqueError));
+    assert!(validator.validate_bson_async(&store, &doc! {"category": ObjectId::new()}).await.is_ok());
+
+    // Malformed extended JSON is a type error, not a store failure
+    let validator = FormValidator::new()
+        .add("cat", Rule::exists("categories", "_id"))
+        .add("category", Rule::unique("posts", "category", None));
+    for value in [json!({"$oid": "nothex"}), json!({"$date": "garbage"}), json!({"$numberLong": "x"})] {
+        let error = validator.validate_async(&store, &json!({"cat": value, "category": value})).await.unwrap_err();
+        let errors = error.validation_errors().unwrap();
+        assert!(matches!(errors["cat"][0], ValidationError::TypeError { .. }));
+        assert!(matches!(errors["category"][0], ValidationError::TypeError { .. }));
+    }
+
+    // ObjectIds coerced from hex strings
+    let validator = FormValidator::new()
+        .add("cat", Rules::new().coerce().add(Rule::object_id()).add(Rule::exists("categories", "_id")));
+    assert!(validator.validate_async(&store, &json!({"cat": books.to_hex()})).await.is_ok());
+}
+
+#[cfg(feature = "mongodb")]
+#[tokio::test]
+async fn test_unique_options() {
+    use validate_ro::rules::Rule;
+    use validate_ro::store::Filter;
+    use validate_ro::FormValidator;
+
+    let store = MemoryStore::new().with_collection("posts", vec![
+        json!({"id": 1, "tenant_id": "t1", "slug": "Hello-World"}),
+        json!({"id": 2, "tenant_id": "t2", "slug": "draft", "deleted": true}),
+    ]);
+
+    // Scoped by a sibling field
+    let validator = FormValidator::new()
+        .add("slug", Rule::unique("posts", "slug", None).scope("tenant_id"));
+    assert!(validator.validate_async(&store, &json!({"tenant_id": "t1", "slug": "Hello-World"})).await.is_err());
+    assert!(validator.validate_async(&store, &json!({"tenant_id": "t2", "slug": "Hello-World"})).await.is_ok());
+    // Only scalar scope values reach the query, never operator objects
+    for tenant_id in [json!({"$ne": "x"}), json!(["t1"])] {
+        let error = validator.validate_async(&store, &json!({"tenant_id": tenant_id, "slug": "new"})).await.unwrap_err();
+        let errors = error.validation_errors().unwrap();
+        assert!(matches!(errors["slug"][0], validate_ro::error::ValidationError::TypeError { .. }));
+    }
+
+    // Case-insensitive
+    let validator = FormValidator::new()
+        .add("slug", Rule::unique("posts", "slug", None).case_insensitive());
+    assert!(validator.validate_async(&store, &json!({"slug": "hello-world"})).await.is_err());
+    assert!(validator.validate_async(&store, &json!({"slug": "hello-world-2"})).await.is_ok());
+
+    // Case-insensitive lookups ask for a collation, for stores that support one
+    struct CollationStore(std::sync::Mutex<Vec<Query>>);
+
+    #[async_trait]
+    impl Store for CollationStore {
+        async fn count(&self, query: &Query) -> Result<u64, StoreError> {
+            self.0.lock().unwrap().push(query.clone());
+            Ok(0)
+        }
+    }
+
+    let recorder = CollationStore(Default::default());
+    for rule in [Rule::unique("posts", "slug", None).case_insensitive(), Rule::unique("posts", "slug", None).collation("fr")] {
+        let validator = FormValidator::new().add("slug", rule);
+        validator.validate_async(&recorder, &json!({"slug": "Hello"})).await.unwrap();
+    }
+    let queries = recorder.0.into_inner().unwrap();
+    let collations: Vec<_> = queries.iter().map(|query| query.collation.as_deref()).collect();
+    assert_eq!(collations, [Some("en"), Some("fr")]);
+    assert_eq!(queries[0].filters, [Filter::EqIgnoreCase("slug".to_string(), "Hello".to_string())]);
+
+    // Extra filters
+    let validator = FormValidator::new()
+        .add("slug", Rule::unique("posts", "slug", None).filter(Filter::Ne("deleted".to_string(), json!(true))));
+    assert!(validator.validate_async(&store, &json!({"slug": "draft"})).await.is_ok());
+
+    // Custom exclude key
+    let validator = FormValidator::new()
+        .add("slug", Rule::unique("posts", "slug", None).exclude("id", json!(1)));
+    assert!(validator.validate_async(&store, &json!({"slug": "Hello-World"})).await.is_ok());
+    assert!(validator.validate_async(&store, &json!({"slug": "draft"})).await.is_err());
+}
+
+#[tokio::test]
+async fn test_store_errors_are_not_validation_errors() {
+    use validate_ro::rules::Rule;
+    use validate_ro::{rules, FormValidator};
+
+    let validator = FormValidator::new()
+        .add("name", rules![Rule::required()])
+        .add("category", Rule::exists("categories", "slug"));
+
+    let error = validator.validate_json_async(&FailingStore, &json!({"category": "books"})).await.unwrap_err();
+    assert!(matches!(error, AsyncValidationError::Store(_)));
+    assert_eq!(error.source().unwrap().source().unwrap().to_string(), "connection refused");
+
+    // Without a failing rule the field errors are reported as usual
+    let error = validator.validate_json_async(&FailingStore, &json!({})).await.unwrap_err();
+    assert!(error.validation_errors().unwrap().contains_key("name"));
+}
+
+#[tokio::test]
+async fn test_concurrent_fields_keep_order() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use validate_ro::rules::Rule;
+    use validate_ro::FormValidator;
+
+    #[derive(Default)]
+    struct TrackingStore {
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Store for TrackingStore {
+        async fn count(&self, query: &Query) -> Result<u64, StoreError> {
+            let current = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(current, Ordering::SeqCst);
+            for _ in 0..3 {
+                tokio::task::yield_now().await;
+            }
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(if query.collection == "found" { 1 } else { 0 })
+        }
+    }
+
+    let data = json!({"a": 1, "b": 2, "c": 3, "d": 4, "e": 5});
+    let validator = FormValidator::new()
+        .add("e", Rule::exists("missing", "id"))
+        .add("a", Rule::exists("found", "id"))
+        .add("c", Rule::exists("missing", "id"))
+        .add("b", Rule::exists("missing", "id"))
+        .add("d", Rule::exists("found", "id"))
+        .concurrency(3);
+
+    let store = TrackingStore::default();
+    let error = validator.validate_json_async(&store, &data).await.unwrap_err();
+    let keys: Vec<&String> = error.validation_errors().unwrap().keys().collect();
+    assert_eq!(keys, ["e", "c", "b"]);
+    assert_eq!(store.max_in_flight.load(Ordering::SeqCst), 3);
+
+    // break_on_error still reports only the first failing field
+    let store = TrackingStore::default();
+    let error = validator.break_on_error().validate_json_async(&store, &data).await.unwrap_err();
+    let keys: Vec<&String> = error.validation_errors().unwrap().keys().collect();
+    assert_eq!(keys, ["e"]);
+}
+
+#[cfg(feature = "mongodb")]
+#[tokio::test]
+async fn test_validate_many() {
+    use validate_ro::error::ValidationError;
+    use validate_ro::rules::Rule;
+    use validate_ro::transforms::Transform;
+    use validate_ro::{FormValidator, Rules};
+
+    let store = CountingStore::new(MemoryStore::new()
+        .with_collection("users", vec![json!({"email": "taken@example.com"})])
+        .with_collection("teams", vec![json!({"_id": 1}), json!({"_id": 2})]));
+
+    let validator = FormValidator::new()
+        .add("email", Rules::new().transform(Transform::lowercase()).add(Rule::unique("users", "email", None)))
+        .add("team", Rule::exists("teams", "_id"));
+
+    let rows = [
+        json!({"email": "a@example.com", "team": 1}),
+        json!({"email": "Taken@example.com", "team": 2}),
+        json!({"email": "b@example.com", "team": 3}),
+        json!({"email": "A@example.com", "team": 2}),
+    ];
+    let results = validator.validate_many(&store, &rows).await;
+
+    // One query per collection and field
+    assert_eq!(store.queries(), (0, 2));
+
+    assert_eq!(results.len(), 4);
+    assert!(results[0].is_ok());
+    let errors = results[1].as_ref().unwrap_err().validation_errors().unwrap();
+    assert!(matches!(errors.get("email").unwrap().first().unwrap(), ValidationError::UniqueError));
+    let errors = results[2].as_ref().unwrap_err().validation_errors().unwrap();
+    assert!(matches!(errors.get("team").unwrap().first().unwrap(), ValidationError::ExistsError));
+
+    // Duplicate of the first row within the batch
+    let errors = results[3].as_ref().unwrap_err().validation_errors().unwrap();
+    assert!(matches!(errors.get("email").unwrap().first().unwrap(), ValidationError::UniqueError));
+    assert!(!errors.contains_key("team"));
+}
+
+#[cfg(feature = "mongodb")]
+#[tokio::test]
+async fn test_validate_many_lookups() {
+    use validate_ro::error::ValidationError;
+    use validate_ro::rules::Rule;
+    use validate_ro::FormValidator;
+
+    let users = || MemoryStore::new().with_collection("users", vec![json!({"email": "Taken@example.com"})]);
+    let validator = FormValidator::new()
+        .add("email", Rule::unique("users", "email", None).case_insensitive());
+    let rows: Vec<_> = (0..50).map(|i| json!({"email": format!("user{}@example.com", i)}))
+        .chain([json!({"email": "taken@EXAMPLE.com"}), json!({"email": "USER0@example.com"})])
+        .collect();
+
+    // Case-insensitive lookups are batched too
+    let store = CountingStore::new(users());
+    let results = validator.validate_many(&store, &rows).await;
+    assert_eq!(store.queries(), (0, 1));
+    assert!(results[..50].iter().all(|result| result.is_ok()));
+    for result in &results[50..] {
+        let errors = result.as_ref().unwrap_err().validation_errors().unwrap();
+        assert!(matches!(errors["email"][0], ValidationError::UniqueError));
+    }
+
+    // Stores without `distinct` are counted once per record
+    let store = CountingStore { distinct: false, ..CountingStore::new(users()) };
+    let fallback = validator.validate_many(&store, &rows).await;
+    assert_eq!(store.queries(), (52, 0));
+    let outcome = |results: &[Result<_, validate_ro::error::AsyncValidationError>]| {
+        results.iter().map(|result| result.is_ok()).collect::<Vec<_>>()
+    };
+    assert_eq!(outcome(&fallback), outcome(&results));
+}
+
+#[cfg(feature = "mongodb")]
+#[tokio::test]
+async fn test_validate_many_matches_per_record_lookups() {
+    use validate_ro::rules::Rule;
+    use validate_ro::FormValidator;
+
+    let store = || MemoryStore::new().with_collection("items", vec![
+        json!({"_id": 5}),
+        json!({"_id": {"$oid": "65a1b2c3d4e5f60718293a4b"}}),
+        json!({"_id": 9007199254740992_i64}),
+        json!({"at": {"$date": "2024-01-01T00:00:20Z"}}),
+    ]);
+    let validator = FormValidator::new()
+        .add("ref", Rule::exists("items", "_id"))
+        .add("at", Rule::exists("items", "at"));
+    let rows = [
+        json!({"ref": {"$numberLong": "5"}}),
+        json!({"ref": {"$oid": "65A1B2C3D4E5F60718293A4B"}}),
+        json!({"ref": 9007199254740993_i64}),
+        json!({"at": {"$date": "2024-01-01T00:00:20.000Z"}}),
+    ];
+
+    // Batched lookups agree with the ones made record by record
+    let mut per_record = Vec::new();
+    for row in &rows {
+        per_record.push(validator.validate_async(&store(), row).await.is_ok());
+    }
+    assert_eq!(per_record, [true, true, false, true]);
+    let batched = CountingStore::new(store());
+    let results = validator.validate_many(&batched, &rows).await;
+    assert_eq!(batched.queries().0, 0);
+    assert_eq!(results.iter().map(Result::is_ok).collect::<Vec<_>>(), per_record);
+
+    // In-batch duplicates compare the same way
+    let validator = FormValidator::new().add("n", Rule::unique("items", "n", None));
+    let rows = [json!({"n": 9007199254740992_i64}), json!({"n": 9007199254740993_i64})];
+    assert!(validator.validate_many(&store(), &rows).await.iter().all(Result::is_ok));
+    let rows = [json!({"n": {"$numberLong": "7"}}), json!({"n": 7})];
+    let results = validator.validate_many(&store(), &rows).await;
+    assert!(results[0].is_ok() && results[1].is_err());
+}
+
+#[tokio::test]
+async fn test_timeouts() {
+    use std::time::Duration;
+    use validate_ro::error::ValidationError;
+    use validate_ro::rules::Rule;
+    use validate_ro::traits::Validator;
+    use validate_ro::store::Filter;
+    use validate_ro::{FormValidator, Rules};
+
+    struct SlowStore;
+
+    #[async_trait]
+    impl Store for SlowStore {
+        async fn count(&self, _query: &Query) -> Result<u64, StoreError> {
+            futures_timer::Delay::new(Duration::from_secs(5)).await;
+            Ok(1)
+        }
+    }
+
+    let data = json!({"category": "books"});
+
+    // Per form
+    let validator = FormValidator::new()
+        .add("category", Rule::exists("categories", "slug"))
+        .timeout(Duration::from_millis(20));
+    let error = validator.validate_json_async(&SlowStore, &data).await.unwrap_err();
+    assert!(matches!(error, AsyncValidationError::Timeout));
+
+    // Per validator
+    let rules = Rules::new().add(Rule::exists("categories", "slug")).timeout(Duration::from_millis(20));
+    assert!(matches!(rules.validate_async(&SlowStore, &json!("books")).await, Err(ValidationError::Timeout)));
+    let validator = FormValidator::new().add("category", rules);
+    let error = validator.validate_json_async(&SlowStore, &data).await.unwrap_err();
+    assert!(matches!(error, AsyncValidationError::Timeout));
+
+    // Earlier validation errors don't hide a timeout
+    let rules = Rules::new()
+        .collect_all()
+        .add(Rule::min_length(10))
+        .add(Rule::exists("categories", "slug"))
+        .timeout(Duration::from_millis(20));
+    assert!(matches!(rules.validate_async(&SlowStore, &json!("books")).await, Err(ValidationError::Timeout)));
+    let nested = FormValidator::new().add("category", Rules::new().add(rules));
+    let error = nested.validate_json_async(&SlowStore, &data).await.unwrap_err();
+    assert!(matches!(error, AsyncValidationError::Timeout));
+
+    // Fast enough stores are unaffected
+    let store = MemoryStore::new().with_collection("categories", vec![json!({"slug": "books"})]);
+    assert!(validator.validate_json_async(&store, &data).await.is_ok());
+
+    // Per record in batches
+    struct LaggyStore(MemoryStore);
+
+    #[async_trait]
+    impl Store for LaggyStore {
+        async fn count(&self, query: &Query) -> Result<u64, StoreError> {
+            let slow = query.filters.contains(&Filter::Eq("slug".to_string(), json!("slow")));
+            futures_timer::Delay::new(Duration::from_millis(if slow { 5000 } else { 10 })).await;
+            self.0.count(query).await
+        }
+    }
+
+    let validator = FormValidator::new()
+        .add("category", Rule::exists("categories", "slug"))
+        .timeout(Duration::from_millis(100));
+    let mut rows = vec![json!({"category": "books"}); 20];
+    rows[3] = json!({"category": "slow"});
+    let results = validator.validate_many_json(&LaggyStore(store), &rows).await;
+    assert!(matches!(results[3], Err(AsyncValidationError::Timeout)));
+    assert!(results.iter().enumerate().all(|(i, result)| i == 3 || result.is_ok()));
+}
@@ -0,0 +1,28 @@
+use validate_ro::transforms::*;
+use serde_json::{json, Value};
+use validate_ro::traits::Transformer;
+
+#[test]
+fn test_string_transformers() {
+    assert_eq!(Transform::trim().transform(json!("  abc \n")), json!("abc"));
+    assert_eq!(Transform::lowercase().transform(json!("AbC")), json!("abc"));
+    assert_eq!(Transform::uppercase().transform(json!("AbC")), json!("ABC"));
+    assert_eq!(Transform::collapse_whitespace().transform(json!(" a  b\t\nc ")), json!("a b c"));
+    assert_eq!(Transform::strip_html().transform(json!("<p>Hi <a href=\"#\">there</a></p>")), json!("Hi there"));
+    assert_eq!(Transform::strip_html().transform(json!("1 < 2 and 3 > 2")), json!("1 < 2 and 3 > 2"));
+    assert_eq!(Transform::strip_html().transform(json!("a<!-- c -->b</br>")), json!("ab"));
+    assert_eq!(Transform::nfc().transform(json!("Cafe\u{301}")), json!("Caf\u{e9}"));
+
+    // Other types pass through unchanged
+    assert_eq!(Transform::trim().transform(json!(12)), json!(12));
+    assert_eq!(Transform::lowercase().transform(Value::Null), Value::Null);
+}
+
+#[test]
+fn test_round() {
+    assert_eq!(Transform::round(2).transform(json!(1.005001)), json!(1.01));
+    assert_eq!(Transform::round(1).transform(json!(-2.25)), json!(-2.3));
+    assert_eq!(Transform::round(0).transform(json!(7.6)), json!(8));
+    assert_eq!(Transform::round(2).transform(json!(7)), json!(7));
+    assert_eq!(Transform::round(2).transform(json!("7.123")), json!("7.123"));
+}